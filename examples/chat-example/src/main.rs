@@ -43,6 +43,8 @@ async fn main() {
 
     let matchmaking = client.matchmaking();
     let networking = client.networking();
+    let utils = client.utils();
+    utils.init_filter_text();
 
     let mut state = GameState {
         state: Box::new(States::Menu(MenuState {
@@ -192,6 +194,8 @@ async fn main() {
                         }
 
                         if let Ok(message) = String::from_utf8(Vec::from(buffer)) {
+                            let message =
+                                utils.filter_text(TextFilteringContext::Chat, sender, &message);
                             println!("GOT MESSAGE: {}", message);
                             messages.push(message);
                         }
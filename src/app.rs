@@ -1,4 +1,7 @@
 use super::*;
+use std::os::raw::{c_char, c_int};
+
+const CALLBACK_BASE_ID: i32 = 1000;
 
 /// An id for a steam app/game
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -10,10 +13,20 @@ impl From<u32> for AppId {
     }
 }
 
+/// An id for a depot of a steam app
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DepotId(pub u32);
+impl From<u32> for DepotId {
+    fn from(id: u32) -> Self {
+        DepotId(id)
+    }
+}
+
 /// Access to the steam apps interface
 pub struct Apps<Manager> {
     pub(crate) apps: *mut sys::ISteamApps,
-    pub(crate) _inner: Arc<Inner<Manager>>,
+    pub(crate) inner: Arc<Inner<Manager>>,
 }
 
 impl<Manager> Apps<Manager> {
@@ -31,6 +44,129 @@ impl<Manager> Apps<Manager> {
         unsafe { sys::SteamAPI_ISteamApps_BIsDlcInstalled(self.apps, app_id.0) }
     }
 
+    /// Returns the number of DLC for the current app, including DLC
+    /// the user doesn't own.
+    pub fn dlc_count(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamApps_GetDLCCount(self.apps).max(0) as u32 }
+    }
+
+    /// Returns the DLC at the given index, if any.
+    ///
+    /// Indices range from `0` to `dlc_count() - 1`.
+    pub fn dlc(&self, index: u32) -> Option<DlcInfo> {
+        unsafe {
+            let mut app_id = 0;
+            let mut available = false;
+            let mut name = [0 as c_char; 128];
+            if sys::SteamAPI_ISteamApps_BGetDLCDataByIndex(
+                self.apps,
+                index as c_int,
+                &mut app_id,
+                &mut available,
+                name.as_mut_ptr(),
+                name.len() as c_int,
+            ) {
+                Some(DlcInfo {
+                    app_id: AppId(app_id),
+                    available,
+                    name: string_from_buffer(&name),
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns all DLC for the current app.
+    pub fn dlcs(&self) -> Vec<DlcInfo> {
+        (0..self.dlc_count())
+            .filter_map(|index| self.dlc(index))
+            .collect()
+    }
+
+    /// Asks steam to download and install an optional DLC.
+    ///
+    /// `DlcInstalled` is posted once the DLC is installed.
+    pub fn install_dlc(&self, app_id: AppId) {
+        unsafe { sys::SteamAPI_ISteamApps_InstallDLC(self.apps, app_id.0) }
+    }
+
+    /// Asks steam to uninstall an optional DLC.
+    pub fn uninstall_dlc(&self, app_id: AppId) {
+        unsafe { sys::SteamAPI_ISteamApps_UninstallDLC(self.apps, app_id.0) }
+    }
+
+    /// Returns the bytes downloaded and the total bytes of a DLC that is
+    /// being downloaded.
+    ///
+    /// Returns `None` if the DLC isn't downloading.
+    pub fn dlc_download_progress(&self, app_id: AppId) -> Option<(u64, u64)> {
+        unsafe {
+            let mut downloaded = 0u64;
+            let mut total = 0u64;
+            if sys::SteamAPI_ISteamApps_GetDlcDownloadProgress(
+                self.apps,
+                app_id.0,
+                &mut downloaded,
+                &mut total,
+            ) {
+                Some((downloaded, total))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the value of a launch query parameter, e.g. `server` for
+    /// `steam://run/<appid>//?server=127.0.0.1`.
+    ///
+    /// Returns `None` if the parameter isn't set. Only keys starting with
+    /// `@` or listed in the app's launch settings are passed to the game.
+    pub fn launch_query_param(&self, key: &str) -> Option<String> {
+        let key = CString::new(key).ok()?;
+        unsafe {
+            let value = sys::SteamAPI_ISteamApps_GetLaunchQueryParam(self.apps, key.as_ptr());
+            if value.is_null() {
+                return None;
+            }
+            let value = CStr::from_ptr(value).to_string_lossy();
+            if value.is_empty() {
+                None
+            } else {
+                Some(value.into_owned())
+            }
+        }
+    }
+
+    /// Returns the command line the game was launched with through a
+    /// `steam://run/<appid>//<command line>` link.
+    ///
+    /// This is empty if the game wasn't launched through such a link.
+    /// `NewUrlLaunchParameters` is posted when it changes while the game
+    /// is running.
+    ///
+    /// The SDK doesn't document a maximum length, so the buffer is grown
+    /// until the whole command line fits. Rich presence joins are limited
+    /// to the 256 byte `connect` value and fit the first buffer.
+    pub fn launch_command_line(&self) -> String {
+        let buffer = fill_growing(1024, |buffer: &mut [c_char]| unsafe {
+            let len = sys::SteamAPI_ISteamApps_GetLaunchCommandLine(
+                self.apps,
+                buffer.as_mut_ptr(),
+                buffer.len() as c_int,
+            );
+            // Counts the nul, a full buffer may have been truncated
+            len.max(0) as usize + 1
+        });
+        string_from_buffer(&buffer)
+    }
+
+    /// Parses the `+connect`, `+password` and `+lobby` commands out of
+    /// `launch_command_line`.
+    pub fn launch_commands(&self) -> LaunchCommands {
+        LaunchCommands::parse(&self.launch_command_line())
+    }
+
     /// Returns whether the user is subscribed to the app with the given
     /// ID.
     ///
@@ -45,6 +181,49 @@ impl<Manager> Apps<Manager> {
         unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend(self.apps) }
     }
 
+    /// Returns whether the user borrowed the current app through
+    /// family sharing.
+    ///
+    /// `app_owner` returns the steam id of the lender.
+    pub fn is_subscribed_from_family_sharing(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamApps_BIsSubscribedFromFamilySharing(self.apps) }
+    }
+
+    /// Returns the limits of the timed trial the user is playing the
+    /// current app as, if any.
+    pub fn timed_trial(&self) -> Option<TimedTrial> {
+        unsafe {
+            let mut seconds_allowed = 0;
+            let mut seconds_played = 0;
+            if sys::SteamAPI_ISteamApps_BIsTimedTrial(
+                self.apps,
+                &mut seconds_allowed,
+                &mut seconds_played,
+            ) {
+                Some(TimedTrial {
+                    seconds_allowed,
+                    seconds_played,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the unix time the user first purchased the app with the
+    /// given ID.
+    ///
+    /// Returns `None` if the user doesn't own the app.
+    pub fn earliest_purchase_time(&self, app_id: AppId) -> Option<u32> {
+        let time =
+            unsafe { sys::SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(self.apps, app_id.0) };
+        if time == 0 {
+            None
+        } else {
+            Some(time)
+        }
+    }
+
     /// Returns whether the user has a VAC ban on their account.
     pub fn is_vac_banned(&self) -> bool {
         unsafe { sys::SteamAPI_ISteamApps_BIsVACBanned(self.apps) }
@@ -90,6 +269,68 @@ impl<Manager> Apps<Manager> {
         }
     }
 
+    /// Returns the installed depots of the app with the given ID in
+    /// mount order.
+    pub fn installed_depots(&self, app_id: AppId) -> Vec<DepotId> {
+        let depots = fill_growing(64, |depots: &mut [sys::DepotId_t]| unsafe {
+            sys::SteamAPI_ISteamApps_GetInstalledDepots(
+                self.apps,
+                app_id.0,
+                depots.as_mut_ptr(),
+                depots.len() as u32,
+            ) as usize
+        });
+        depots.into_iter().map(DepotId).collect()
+    }
+
+    /// Requests the size, SHA1 and flags steam has on record for a file
+    /// in the app's depots.
+    ///
+    /// `path` is relative to the app's install directory.
+    pub fn file_details<F>(&self, path: &str, cb: F) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(SResult<FileDetails>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.file_details_call(path, cb))
+    }
+
+    /// Async version of [`file_details`](#method.file_details)
+    pub fn file_details_async(
+        &self,
+        path: &str,
+    ) -> CallResultFuture<SResult<FileDetails>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| self.file_details_call(path, cb))
+    }
+
+    fn file_details_call<F>(&self, path: &str, cb: F) -> sys::SteamAPICall_t
+    where
+        F: FnOnce(SResult<FileDetails>) + 'static + Send,
+    {
+        unsafe {
+            let path = CString::new(path).unwrap();
+            let api_call = sys::SteamAPI_ISteamApps_GetFileDetails(self.apps, path.as_ptr());
+            register_call_result::<sys::FileDetailsResult_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 23,
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if v.m_eResult != sys::EResult::k_EResultOK => {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(v) => Ok(FileDetails {
+                            size: v.m_ulFileSize,
+                            sha1: v.m_FileSHA,
+                            flags: v.m_unFlags,
+                        }),
+                    })
+                },
+            );
+            api_call
+        }
+    }
+
     /// Returns the steam id of the original owner of the app.
     ///
     /// Differs from the current user if the app is borrowed.
@@ -139,3 +380,241 @@ impl<Manager> Apps<Manager> {
         }
     }
 }
+
+/// The commands commonly used to route a player into a game from a
+/// `steam://run` link or a rich presence join string.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LaunchCommands {
+    /// The address passed with `+connect <address>`
+    pub connect: Option<String>,
+    /// The server password passed with `+password <password>`
+    pub password: Option<String>,
+    /// The lobby passed with `+lobby <id>` or `+connect_lobby <id>`
+    pub lobby: Option<LobbyId>,
+}
+
+impl LaunchCommands {
+    /// Parses a command line such as `+connect 127.0.0.1:27015 +password "a b"`.
+    ///
+    /// Values may be quoted. Unknown tokens are ignored, and lobby ids may
+    /// be given in any form `SteamId` accepts.
+    pub fn parse(command_line: &str) -> LaunchCommands {
+        let mut commands = LaunchCommands::default();
+        let mut tokens = tokenize(command_line).into_iter();
+        while let Some(token) = tokens.next() {
+            let slot = match token.as_str() {
+                "+connect" => &mut commands.connect,
+                "+password" => &mut commands.password,
+                "+lobby" | "+connect_lobby" => {
+                    if let Some(lobby) = tokens.next().and_then(|v| v.parse::<SteamId>().ok()) {
+                        commands.lobby = Some(LobbyId(lobby.raw()));
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some(value) = tokens.next() {
+                *slot = Some(value);
+            }
+        }
+        commands
+    }
+
+    /// Returns whether none of the commands were found
+    pub fn is_empty(&self) -> bool {
+        self.connect.is_none() && self.password.is_none() && self.lobby.is_none()
+    }
+}
+
+/// Splits a command line on whitespace, keeping double quoted
+/// sections together.
+fn tokenize(command_line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quoted = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_token = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// The limits of a timed trial
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimedTrial {
+    /// How long the user may play the app for, in seconds
+    pub seconds_allowed: u32,
+    /// How long the user has played the app for, in seconds
+    pub seconds_played: u32,
+}
+
+impl TimedTrial {
+    /// Returns how many seconds of the trial are left
+    pub fn seconds_remaining(&self) -> u32 {
+        self.seconds_allowed.saturating_sub(self.seconds_played)
+    }
+
+    /// Returns whether the trial has run out
+    pub fn is_expired(&self) -> bool {
+        self.seconds_remaining() == 0
+    }
+}
+
+/// The details steam has on record for a file in the app's depots
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileDetails {
+    /// The size of the file in bytes
+    pub size: u64,
+    /// The SHA1 hash of the file's contents
+    pub sha1: [u8; 20],
+    /// The file's flags as reported by steam
+    pub flags: u32,
+}
+
+impl FileDetails {
+    /// Returns the SHA1 hash as a lowercase hex string
+    pub fn sha1_hex(&self) -> String {
+        self.sha1.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Returns whether a local file with the given size and SHA1
+    /// hash matches this record.
+    pub fn matches(&self, size: u64, sha1: &[u8; 20]) -> bool {
+        self.size == size && &self.sha1 == sha1
+    }
+}
+
+/// A DLC of the current app
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DlcInfo {
+    /// The app id of the DLC
+    pub app_id: AppId,
+    /// Whether the DLC is available on the store
+    pub available: bool,
+    /// The name of the DLC
+    pub name: String,
+}
+
+/// Called when the user installs a DLC mid-session
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DlcInstalled {
+    /// The app id of the DLC that was installed
+    pub app_id: AppId,
+}
+
+unsafe impl Callback for DlcInstalled {
+    const ID: i32 = CALLBACK_BASE_ID + 5;
+    const SIZE: i32 = ::std::mem::size_of::<sys::DlcInstalled_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::DlcInstalled_t);
+        DlcInstalled {
+            app_id: AppId(val.m_nAppID),
+        }
+    }
+}
+
+/// Called when the game is already running and the user follows a
+/// `steam://run/<appid>//<command line>` link or accepts a rich
+/// presence join.
+///
+/// The new parameters can be read with `Apps::launch_command_line`
+/// and `Apps::launch_query_param`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NewUrlLaunchParameters;
+
+unsafe impl Callback for NewUrlLaunchParameters {
+    const ID: i32 = CALLBACK_BASE_ID + 14;
+    const SIZE: i32 = ::std::mem::size_of::<sys::NewUrlLaunchParameters_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        NewUrlLaunchParameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_launch_commands() {
+        let commands = LaunchCommands::parse("+connect 127.0.0.1:27015 +password hunter2");
+        assert_eq!(commands.connect.as_deref(), Some("127.0.0.1:27015"));
+        assert_eq!(commands.password.as_deref(), Some("hunter2"));
+        assert_eq!(commands.lobby, None);
+
+        let commands = LaunchCommands::parse("-novid +connect_lobby 109775240917176337");
+        assert_eq!(commands.lobby, Some(LobbyId(109775240917176337)));
+        assert_eq!(commands.connect, None);
+
+        let commands = LaunchCommands::parse("+lobby [L:1:20497] +password \"two words\"");
+        assert_eq!(commands.lobby.map(|l| l.raw()), Some(109212290963755025));
+        assert_eq!(commands.password.as_deref(), Some("two words"));
+
+        assert!(LaunchCommands::parse("").is_empty());
+        assert!(LaunchCommands::parse("+connect").is_empty());
+        assert!(LaunchCommands::parse("+lobby nope").is_empty());
+    }
+
+    #[test]
+    fn timed_trial_remaining() {
+        let trial = TimedTrial {
+            seconds_allowed: 3600,
+            seconds_played: 600,
+        };
+        assert_eq!(trial.seconds_remaining(), 3000);
+        assert!(!trial.is_expired());
+
+        // Steam may report more playtime than allowed
+        let trial = TimedTrial {
+            seconds_allowed: 3600,
+            seconds_played: 3700,
+        };
+        assert_eq!(trial.seconds_remaining(), 0);
+        assert!(trial.is_expired());
+    }
+
+    #[test]
+    fn file_details_sha1() {
+        let mut sha1 = [0xab; 20];
+        sha1[19] = 0x01;
+        let details = FileDetails {
+            size: 5,
+            sha1,
+            flags: 0,
+        };
+        assert_eq!(details.sha1_hex(), "ab".repeat(19) + "01");
+        assert!(details.matches(5, &sha1));
+        assert!(!details.matches(6, &sha1));
+        assert!(!details.matches(5, &[0xab; 20]));
+    }
+
+    #[test]
+    fn tokenize_quotes() {
+        assert_eq!(tokenize("  a \"b c\"  \"\" d"), vec!["a", "b c", "", "d"]);
+    }
+}
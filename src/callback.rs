@@ -2,7 +2,20 @@ use super::*;
 
 use crate::sys;
 
-use std::sync::{Arc, Weak};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, PoisonError, Weak};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
 
 pub unsafe trait Callback {
     const ID: i32;
@@ -13,9 +26,14 @@ pub unsafe trait Callback {
 /// A handle that can be used to remove a callback
 /// at a later point.
 ///
+/// Multiple handlers can be registered for the same callback
+/// type, each handle only removes the handler it was returned
+/// for.
+///
 /// Removes the callback when dropped
 pub struct CallbackHandle<Manager = ClientManager> {
     id: i32,
+    subscription: u64,
     inner: Weak<Inner<Manager>>,
 }
 unsafe impl<Manager> Send for CallbackHandle<Manager> {}
@@ -25,14 +43,124 @@ impl<Manager> Drop for CallbackHandle<Manager> {
         if let Some(inner) = self.inner.upgrade() {
             match inner.callbacks.lock() {
                 Ok(mut cb) => {
-                    cb.callbacks.remove(&self.id);
+                    cb.remove(self.id, self.subscription);
                 }
                 Err(err) => {
-                    eprintln!("error while dropping callback: {:?}", err);
+                    report_error!("error while dropping callback: {:?}", err);
+                }
+            }
+        }
+    }
+}
+
+/// A registered callback handler.
+///
+/// Shared with `dispatch` so that handlers can run without the
+/// callbacks lock being held.
+pub(crate) struct Handler {
+    subscription: u64,
+    /// Set once the handle is dropped, so that a running dispatch
+    /// skips the handler
+    removed: AtomicBool,
+    f: Mutex<CallbackFn>,
+}
+
+impl Callbacks {
+    pub(crate) fn new() -> Callbacks {
+        Callbacks {
+            callbacks: HashMap::new(),
+            next_subscription: 0,
+            call_results: HashMap::new(),
+        }
+    }
+
+    /// Adds a handler for the given callback id and returns the
+    /// subscription id that identifies it.
+    fn insert(&mut self, id: i32, f: CallbackFn) -> u64 {
+        let subscription = self.next_subscription;
+        self.next_subscription += 1;
+        self.callbacks
+            .entry(id)
+            .or_default()
+            .push(Arc::new(Handler {
+                subscription,
+                removed: AtomicBool::new(false),
+                f: Mutex::new(f),
+            }));
+        subscription
+    }
+
+    /// Removes a single handler, leaving any other handlers for
+    /// the same callback id in place.
+    fn remove(&mut self, id: i32, subscription: u64) {
+        if let Some(handlers) = self.callbacks.get_mut(&id) {
+            handlers.retain(|handler| {
+                if handler.subscription == subscription {
+                    handler.removed.store(true, Ordering::SeqCst);
+                    false
+                } else {
+                    true
                 }
+            });
+            if handlers.is_empty() {
+                self.callbacks.remove(&id);
             }
         }
     }
+
+    /// Removes every pending call result whose deadline has passed
+    /// so that the caller can time them out once the lock is released.
+    pub(crate) fn expired_call_results(
+        &mut self,
+        now: Instant,
+    ) -> Vec<(sys::SteamAPICall_t, CallResultFn)> {
+        let expired: Vec<_> = self
+            .call_results
+            .iter()
+            .filter(|(_, pending)| pending.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(api_call, _)| *api_call)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|api_call| {
+                self.call_results
+                    .remove(&api_call)
+                    .map(|pending| (api_call, pending.callback))
+            })
+            .collect()
+    }
+}
+
+/// Runs every handler registered for the callback id in the
+/// order they were registered.
+///
+/// The handlers run without the callbacks lock held, so they can
+/// register handlers and drop handles, including their own. A handler
+/// removed while the callback is being dispatched isn't run anymore.
+///
+/// A panicking handler doesn't stop the others from running,
+/// the panics are returned instead.
+pub(crate) unsafe fn dispatch<Manager>(
+    inner: &Inner<Manager>,
+    id: i32,
+    param: *mut c_void,
+) -> Vec<CallbackPanic> {
+    let handlers = {
+        let callbacks = inner.callbacks.lock().unwrap();
+        callbacks.callbacks.get(&id).cloned().unwrap_or_default()
+    };
+    let mut panics = Vec::new();
+    for handler in handlers {
+        if handler.removed.load(Ordering::SeqCst) {
+            continue;
+        }
+        // A handler that panicked before is still run
+        let mut f = handler.f.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(panic) = CallbackPanic::catch(Some(id), || f(param)) {
+            panics.push(panic);
+        }
+    }
+    panics
 }
 
 pub(crate) unsafe fn register_callback<C, F, Manager>(
@@ -43,33 +171,853 @@ where
     C: Callback,
     F: FnMut(C) + Send + 'static,
 {
-    {
+    let subscription = {
         let mut callbacks = inner.callbacks.lock().unwrap();
-        callbacks.callbacks.insert(
+        callbacks.insert(
             C::ID,
             Box::new(move |param| {
                 let param = C::from_raw(param);
                 f(param)
             }),
-        );
-    }
+        )
+    };
     CallbackHandle {
         id: C::ID,
+        subscription,
         inner: Arc::downgrade(&inner),
     }
 }
 
+/// Registers the handler for the result of an api call.
+///
+/// The handler is passed `Err(SteamError::IOFailure)` if steam failed
+/// to deliver the result and `Err(SteamError::Timeout)` if the deadline
+/// set through the call's `CallResultHandle` passes first.
 pub(crate) unsafe fn register_call_result<C, F, Manager>(
     inner: &Arc<Inner<Manager>>,
     api_call: sys::SteamAPICall_t,
     _callback_id: i32,
     f: F,
 ) where
-    F: for<'a> FnOnce(&'a C, bool) + 'static + Send,
+    F: for<'a> FnOnce(Result<&'a C, SteamError>) + 'static + Send,
 {
     let mut callbacks = inner.callbacks.lock().unwrap();
     callbacks.call_results.insert(
         api_call,
-        Box::new(move |param, failed| f(&*(param as *const C), failed)),
+        PendingCallResult {
+            callback: Box::new(move |param| f(param.map(|param| &*(param as *const C)))),
+            deadline: None,
+        },
     );
 }
+
+/// A handle to the result of an asynchronous steam api call.
+///
+/// Returned by every method that takes a call result closure. The
+/// handle can be used to stop waiting for the result, either
+/// explicitly with `cancel` or by setting a deadline after which the
+/// closure is called with `SteamError::Timeout` (or the closest error
+/// its result type has). Deadlines are checked by
+/// `SingleClient::run_callbacks`.
+///
+/// Dropping the handle does not cancel the call.
+pub struct CallResultHandle<Manager = ClientManager> {
+    api_call: sys::SteamAPICall_t,
+    inner: Weak<Inner<Manager>>,
+    /// Cleared for handles to the call of a `CallResultFuture`, which
+    /// would never resolve if the call was cancelled
+    cancellable: bool,
+}
+unsafe impl<Manager> Send for CallResultHandle<Manager> {}
+unsafe impl<Manager> Sync for CallResultHandle<Manager> {}
+
+impl<Manager> Clone for CallResultHandle<Manager> {
+    fn clone(&self) -> Self {
+        CallResultHandle {
+            api_call: self.api_call,
+            inner: self.inner.clone(),
+            cancellable: self.cancellable,
+        }
+    }
+}
+
+impl<Manager> CallResultHandle<Manager> {
+    pub(crate) fn new(inner: &Arc<Inner<Manager>>, api_call: sys::SteamAPICall_t) -> Self {
+        CallResultHandle {
+            api_call,
+            inner: Arc::downgrade(inner),
+            cancellable: true,
+        }
+    }
+
+    fn with_pending<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Callbacks) -> Option<R>,
+    {
+        let inner = self.inner.upgrade()?;
+        let mut callbacks = inner.callbacks.lock().ok()?;
+        f(&mut callbacks)
+    }
+
+    /// Returns whether the result is still being waited on
+    pub fn is_pending(&self) -> bool {
+        self.with_pending(|cb| cb.call_results.get(&self.api_call).map(|_| ()))
+            .is_some()
+    }
+
+    /// Stops waiting for the result without calling the closure.
+    ///
+    /// Returns false if the result had already been delivered,
+    /// timed out or was cancelled before. Calls made by an `_async`
+    /// method can't be cancelled, drop their future instead.
+    pub fn cancel(&self) -> bool {
+        if !self.cancellable {
+            return false;
+        }
+        self.with_pending(|cb| cb.call_results.remove(&self.api_call))
+            .is_some()
+    }
+
+    /// Gives up on the result if it hasn't arrived by the deadline.
+    ///
+    /// Replaces any previously set deadline. Does nothing if the
+    /// result isn't pending anymore.
+    pub fn set_deadline(&self, deadline: Instant) {
+        self.with_pending(|cb| {
+            cb.call_results
+                .get_mut(&self.api_call)
+                .map(|pending| pending.deadline = Some(deadline))
+        });
+    }
+
+    /// Gives up on the result if it hasn't arrived within the timeout
+    pub fn set_timeout(&self, timeout: Duration) {
+        self.set_deadline(Instant::now() + timeout);
+    }
+}
+
+/// A receiver for every callback of a single type.
+///
+/// Created by `Client::subscribe`. Callbacks are queued while
+/// `SingleClient::run_callbacks` is called and can be drained
+/// at any later point (e.g. once per frame).
+///
+/// The subscription is removed when the receiver is dropped.
+pub struct CallbackReceiver<C, Manager = ClientManager> {
+    receiver: Receiver<C>,
+    _handle: CallbackHandle<Manager>,
+}
+
+impl<C, Manager> CallbackReceiver<C, Manager> {
+    /// Returns the next queued callback if any, without blocking.
+    pub fn try_recv(&self) -> Option<C> {
+        match self.receiver.try_recv() {
+            Ok(val) => Some(val),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Blocks until the next callback arrives.
+    ///
+    /// Returns `None` once the client has been shut down. Another thread
+    /// must be calling `SingleClient::run_callbacks` for this to return.
+    pub fn recv(&self) -> Option<C> {
+        self.receiver.recv().ok()
+    }
+
+    /// Blocks until the next callback arrives or the timeout elapses.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<C> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(val) => Some(val),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Returns an iterator over all currently queued callbacks.
+    ///
+    /// This never blocks.
+    pub fn try_iter(&self) -> impl Iterator<Item = C> + '_ {
+        self.receiver.try_iter()
+    }
+}
+
+pub(crate) unsafe fn subscribe<C, Manager>(
+    inner: &Arc<Inner<Manager>>,
+) -> CallbackReceiver<C, Manager>
+where
+    C: Callback + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let handle = register_callback(inner, move |val: C| {
+        let _ = sender.send(val);
+    });
+    CallbackReceiver {
+        receiver,
+        _handle: handle,
+    }
+}
+
+/// An asynchronous stream of every callback of a single type.
+///
+/// Created by `Client::subscribe_stream`. Callbacks are queued while
+/// `SingleClient::run_callbacks` is called and the stream is woken
+/// when new values arrive. The stream ends once the client has been
+/// shut down.
+///
+/// The subscription is removed when the stream is dropped.
+pub struct CallbackStream<C, Manager = ClientManager> {
+    state: Arc<Mutex<CallbackStreamState<C>>>,
+    _handle: CallbackHandle<Manager>,
+}
+
+struct CallbackStreamState<C> {
+    queue: VecDeque<C>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+/// The half of a `CallbackStream` owned by the callback registry.
+///
+/// Closes the stream when the registry drops it.
+struct CallbackStreamSender<C> {
+    state: Arc<Mutex<CallbackStreamState<C>>>,
+}
+
+impl<C> CallbackStreamSender<C> {
+    fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut CallbackStreamState<C>),
+    {
+        let waker = match self.state.lock() {
+            Ok(mut state) => {
+                f(&mut state);
+                state.waker.take()
+            }
+            Err(_) => None,
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<C> Drop for CallbackStreamSender<C> {
+    fn drop(&mut self) {
+        self.update(|state| state.closed = true);
+    }
+}
+
+impl<C, Manager> Stream for CallbackStream<C, Manager> {
+    type Item = C;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<C>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(val) = state.queue.pop_front() {
+            Poll::Ready(Some(val))
+        } else if state.closed {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+pub(crate) unsafe fn subscribe_stream<C, Manager>(
+    inner: &Arc<Inner<Manager>>,
+) -> CallbackStream<C, Manager>
+where
+    C: Callback + Send + 'static,
+{
+    let state = Arc::new(Mutex::new(CallbackStreamState {
+        queue: VecDeque::new(),
+        closed: false,
+        waker: None,
+    }));
+    let sender = CallbackStreamSender {
+        state: state.clone(),
+    };
+    let handle = register_callback(inner, move |val: C| {
+        sender.update(|state| state.queue.push_back(val));
+    });
+    CallbackStream {
+        state,
+        _handle: handle,
+    }
+}
+
+/// A future that resolves to the result of an asynchronous
+/// steam api call.
+///
+/// The result is delivered while `SingleClient::run_callbacks`
+/// is being called, so the future only makes progress as long
+/// as callbacks are being run. It does not depend on any specific
+/// executor.
+///
+/// Dropping the future before it resolves unregisters the pending
+/// call result. With `set_deadline` or `set_timeout` the future
+/// resolves to the timeout error if the result doesn't arrive in time.
+#[must_use = "futures do nothing unless polled"]
+pub struct CallResultFuture<T, Manager = ClientManager> {
+    handle: CallResultHandle<Manager>,
+    state: Arc<Mutex<CallResultState<T>>>,
+}
+
+struct CallResultState<T> {
+    result: Option<T>,
+    completed: bool,
+    waker: Option<Waker>,
+}
+
+impl<T, Manager> CallResultFuture<T, Manager>
+where
+    T: Send + 'static,
+{
+    /// Creates a future for the call result registered by `start`.
+    ///
+    /// `start` is passed the completion function that should be
+    /// registered as the call result callback and must return the
+    /// handle of the api call it started.
+    pub(crate) fn new<S>(inner: &Arc<Inner<Manager>>, start: S) -> Self
+    where
+        S: FnOnce(Box<dyn FnOnce(T) + Send>) -> sys::SteamAPICall_t,
+    {
+        let state = Arc::new(Mutex::new(CallResultState {
+            result: None,
+            completed: false,
+            waker: None,
+        }));
+        let complete = {
+            let state = state.clone();
+            Box::new(move |value: T| {
+                let waker = {
+                    let mut state = state.lock().unwrap();
+                    state.result = Some(value);
+                    state.completed = true;
+                    state.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            })
+        };
+        let api_call = start(complete);
+        CallResultFuture {
+            handle: CallResultHandle::new(inner, api_call),
+            state,
+        }
+    }
+}
+
+impl<T, Manager> CallResultFuture<T, Manager> {
+    /// Returns whether the result is still being waited on
+    pub fn is_pending(&self) -> bool {
+        self.handle.is_pending()
+    }
+
+    /// Gives up on the result if it hasn't arrived by the deadline.
+    ///
+    /// See `CallResultHandle::set_deadline`.
+    pub fn set_deadline(&self, deadline: Instant) {
+        self.handle.set_deadline(deadline);
+    }
+
+    /// Gives up on the result if it hasn't arrived within the timeout
+    pub fn set_timeout(&self, timeout: Duration) {
+        self.handle.set_timeout(timeout);
+    }
+
+    /// Returns a handle to the call that can't cancel it, so that the
+    /// future always resolves
+    pub(crate) fn shared_handle(&self) -> CallResultHandle<Manager> {
+        CallResultHandle {
+            cancellable: false,
+            ..self.handle.clone()
+        }
+    }
+}
+
+impl<T, Manager> Future for CallResultFuture<T, Manager> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(result) = state.result.take() {
+            Poll::Ready(result)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, Manager> Drop for CallResultFuture<T, Manager> {
+    fn drop(&mut self) {
+        let completed = match self.state.lock() {
+            Ok(state) => state.completed,
+            Err(_) => false,
+        };
+        if !completed {
+            self.handle.cancel();
+        }
+    }
+}
+
+/// A panic raised by a callback handler.
+///
+/// Handlers are run with the panic caught so that one failing
+/// handler doesn't stop the others or leave the callback registry
+/// unusable. The panics are returned by
+/// `SingleClient::try_run_callbacks`.
+pub struct CallbackPanic {
+    callback_id: Option<i32>,
+    payload: Box<dyn Any + Send + 'static>,
+}
+
+impl CallbackPanic {
+    pub(crate) fn catch<F, R>(callback_id: Option<i32>, f: F) -> Result<R, CallbackPanic>
+    where
+        F: FnOnce() -> R,
+    {
+        panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| CallbackPanic {
+            callback_id,
+            payload,
+        })
+    }
+
+    /// Returns the id of the callback (`Callback::ID`) whose handler
+    /// panicked.
+    ///
+    /// This is `None` for the warning callback set with
+    /// `Utils::set_warning_callback` and for call result handlers
+    /// that were timed out because their deadline passed.
+    pub fn callback_id(&self) -> Option<i32> {
+        self.callback_id
+    }
+
+    /// Returns the panic message if the handler panicked with one
+    pub fn message(&self) -> Option<&str> {
+        if let Some(msg) = self.payload.downcast_ref::<&str>() {
+            Some(msg)
+        } else {
+            self.payload
+                .downcast_ref::<String>()
+                .map(|msg| msg.as_str())
+        }
+    }
+
+    /// Returns the value the handler panicked with
+    pub fn into_payload(self) -> Box<dyn Any + Send + 'static> {
+        self.payload
+    }
+
+    /// Continues unwinding with the original panic payload
+    pub fn resume(self) -> ! {
+        panic::resume_unwind(self.payload)
+    }
+}
+
+impl Debug for CallbackPanic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackPanic")
+            .field("callback_id", &self.callback_id)
+            .field("message", &self.message())
+            .finish()
+    }
+}
+
+/// Runs `SingleClient::run_callbacks` on a background thread.
+///
+/// Created by `SingleClient::spawn_pump`. The thread is stopped and
+/// joined when the pump is dropped, use `stop` to get the
+/// `SingleClient` back instead.
+///
+/// Panics in handlers are reported and don't stop the thread.
+/// Handlers run without the callbacks lock being held, so other
+/// threads can register callbacks and drop handles while a handler
+/// is running on the pump.
+pub struct CallbackPump<Manager = ClientManager> {
+    stop: Sender<()>,
+    thread: Option<JoinHandle<SingleClient<Manager>>>,
+}
+
+impl<Manager> CallbackPump<Manager>
+where
+    Manager: crate::Manager + Send + Sync + 'static,
+{
+    pub(crate) fn spawn(single: SingleClient<Manager>, interval: Duration) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("steam-callbacks".into())
+            .spawn(move || {
+                loop {
+                    for panic in single.try_run_callbacks() {
+                        report_error!(
+                            "callback handler panicked: {}",
+                            panic.message().unwrap_or("unknown panic")
+                        );
+                    }
+                    // Wakes up early once the pump is stopped
+                    match stopped.recv_timeout(interval) {
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                single
+            })
+            .expect("failed to spawn the callback thread");
+        CallbackPump {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<Manager> CallbackPump<Manager> {
+    /// Stops the thread and returns the `SingleClient` it was running.
+    ///
+    /// Waits for the callbacks that are currently being run to finish.
+    /// Panics in handlers were already reported on the thread and are
+    /// not resumed here, only a panic of the thread itself outside of
+    /// any handler is.
+    pub fn stop(mut self) -> SingleClient<Manager> {
+        let _ = self.stop.send(());
+        match self.thread.take().unwrap().join() {
+            Ok(single) => single,
+            Err(err) => std::panic::resume_unwind(err),
+        }
+    }
+}
+
+impl<Manager> Drop for CallbackPump<Manager> {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.stop.send(());
+            if thread.join().is_err() {
+                report_error!("callback thread panicked");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Wake;
+
+    fn test_inner() -> Arc<Inner<()>> {
+        Arc::new(Inner {
+            _manager: (),
+            callbacks: Mutex::new(Callbacks::new()),
+            networking_sockets_data: Mutex::new(NetworkingSocketsData {
+                sockets: Default::default(),
+                independent_connections: Default::default(),
+                connection_callback: Default::default(),
+            }),
+        })
+    }
+
+    fn persona_change(inner: &Arc<Inner<()>>) -> Vec<CallbackPanic> {
+        let mut raw = sys::PersonaStateChange_t {
+            m_ulSteamID: 76561198040894045,
+            m_nChangeFlags: 1,
+        };
+        unsafe { dispatch(inner, PersonaStateChange::ID, &mut raw as *mut _ as *mut _) }
+    }
+
+    #[test]
+    fn multiple_handlers() {
+        let inner = test_inner();
+        let first = Arc::new(AtomicUsize::new(0));
+        let second = Arc::new(AtomicUsize::new(0));
+
+        let first_handle = {
+            let first = first.clone();
+            unsafe {
+                register_callback(&inner, move |p: PersonaStateChange| {
+                    assert_eq!(p.steam_id, SteamId(76561198040894045));
+                    first.fetch_add(1, Ordering::SeqCst);
+                })
+            }
+        };
+        let _second_handle = {
+            let second = second.clone();
+            unsafe {
+                register_callback(&inner, move |_: PersonaStateChange| {
+                    second.fetch_add(1, Ordering::SeqCst);
+                })
+            }
+        };
+
+        persona_change(&inner);
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+        assert_eq!(second.load(Ordering::SeqCst), 1);
+
+        drop(first_handle);
+        persona_change(&inner);
+        assert_eq!(first.load(Ordering::SeqCst), 1);
+        assert_eq!(second.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn panicking_handler() {
+        let inner = test_inner();
+        let count = Arc::new(AtomicUsize::new(0));
+        let _panicking =
+            unsafe { register_callback(&inner, |_: PersonaStateChange| panic!("handler failed")) };
+        let _counting = {
+            let count = count.clone();
+            unsafe {
+                register_callback(&inner, move |_: PersonaStateChange| {
+                    count.fetch_add(1, Ordering::SeqCst);
+                })
+            }
+        };
+
+        let panics = persona_change(&inner);
+        assert_eq!(panics.len(), 1);
+        assert_eq!(panics[0].callback_id(), Some(PersonaStateChange::ID));
+        assert_eq!(panics[0].message(), Some("handler failed"));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // The registry is still usable afterwards
+        assert!(!inner.callbacks.is_poisoned());
+        let _late = unsafe { register_callback(&inner, |_: PersonaStateChange| {}) };
+        assert_eq!(persona_change(&inner).len(), 1);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn handlers_change_registrations() {
+        let inner = test_inner();
+        let count = Arc::new(AtomicUsize::new(0));
+        let own_handle = Arc::new(Mutex::new(None));
+        let registered = Arc::new(Mutex::new(Vec::new()));
+
+        // Drops its own handle and registers another handler, both of
+        // which need the callbacks lock
+        let handle = {
+            let inner = inner.clone();
+            let count = count.clone();
+            let own_handle = own_handle.clone();
+            let registered = registered.clone();
+            unsafe {
+                register_callback(&inner.clone(), move |_: PersonaStateChange| {
+                    count.fetch_add(1, Ordering::SeqCst);
+                    drop(own_handle.lock().unwrap().take());
+                    let handle = register_callback(&inner, |_: PersonaStateChange| {});
+                    registered.lock().unwrap().push(handle);
+                })
+            }
+        };
+        *own_handle.lock().unwrap() = Some(handle);
+
+        assert!(persona_change(&inner).is_empty());
+        assert!(persona_change(&inner).is_empty());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert_eq!(registered.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn handler_removed_during_dispatch() {
+        let inner = test_inner();
+        let count = Arc::new(AtomicUsize::new(0));
+        let second = Arc::new(Mutex::new(None));
+        let _first = {
+            let second = second.clone();
+            unsafe {
+                register_callback(&inner, move |_: PersonaStateChange| {
+                    drop(second.lock().unwrap().take());
+                })
+            }
+        };
+        let handle = {
+            let count = count.clone();
+            unsafe {
+                register_callback(&inner, move |_: PersonaStateChange| {
+                    count.fetch_add(1, Ordering::SeqCst);
+                })
+            }
+        };
+        *second.lock().unwrap() = Some(handle);
+
+        persona_change(&inner);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+    }
+
+    fn lobby_created_future(
+        inner: &Arc<Inner<()>>,
+        api_call: sys::SteamAPICall_t,
+    ) -> CallResultFuture<u64, ()> {
+        CallResultFuture::new(inner, |cb| {
+            unsafe {
+                register_call_result::<sys::LobbyCreated_t, _, _>(inner, api_call, 513, move |v| {
+                    cb(v.map(|v| v.m_ulSteamIDLobby).unwrap_or(0))
+                });
+            }
+            api_call
+        })
+    }
+
+    #[test]
+    fn call_result_future() {
+        let inner = test_inner();
+        let mut future = lobby_created_future(&inner, 1);
+
+        let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let task_waker = Waker::from(waker.clone());
+        let mut cx = Context::from_waker(&task_waker);
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+
+        let cb = inner.callbacks.lock().unwrap().call_results.remove(&1);
+        let mut raw: sys::LobbyCreated_t = unsafe { std::mem::zeroed() };
+        raw.m_ulSteamIDLobby = 109775240917176337;
+        (cb.unwrap().callback)(Ok(&mut raw as *mut _ as *mut _));
+
+        assert_eq!(waker.0.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            Pin::new(&mut future).poll(&mut cx),
+            Poll::Ready(109775240917176337)
+        );
+    }
+
+    #[test]
+    fn dropped_call_result_future() {
+        let inner = test_inner();
+        let future = lobby_created_future(&inner, 2);
+        assert!(inner
+            .callbacks
+            .lock()
+            .unwrap()
+            .call_results
+            .contains_key(&2));
+
+        drop(future);
+        assert!(inner.callbacks.lock().unwrap().call_results.is_empty());
+    }
+
+    #[test]
+    fn call_result_future_timeout() {
+        let inner = test_inner();
+        let mut future = lobby_created_future(&inner, 6);
+        let shared = future.shared_handle();
+        assert!(!shared.cancel());
+        assert!(future.is_pending());
+
+        let now = Instant::now();
+        future.set_deadline(now);
+        let expired = inner.callbacks.lock().unwrap().expired_call_results(now);
+        for (_, cb) in expired {
+            cb(Err(SteamError::Timeout));
+        }
+        assert!(!future.is_pending());
+        let waker = Waker::from(Arc::new(CountingWaker(AtomicUsize::new(0))));
+        assert_eq!(
+            Pin::new(&mut future).poll(&mut Context::from_waker(&waker)),
+            Poll::Ready(0)
+        );
+    }
+
+    fn lobby_created_handle(
+        inner: &Arc<Inner<()>>,
+        api_call: sys::SteamAPICall_t,
+        result: &Arc<Mutex<Option<SResult<u64>>>>,
+    ) -> CallResultHandle<()> {
+        let result = result.clone();
+        unsafe {
+            register_call_result::<sys::LobbyCreated_t, _, _>(inner, api_call, 513, move |v| {
+                *result.lock().unwrap() = Some(v.map(|v| v.m_ulSteamIDLobby));
+            });
+        }
+        CallResultHandle::new(inner, api_call)
+    }
+
+    #[test]
+    fn cancel_call_result() {
+        let inner = test_inner();
+        let result = Arc::new(Mutex::new(None));
+        let handle = lobby_created_handle(&inner, 3, &result);
+        assert!(handle.is_pending());
+
+        assert!(handle.cancel());
+        assert!(!handle.is_pending());
+        assert!(!handle.cancel());
+        assert!(inner.callbacks.lock().unwrap().call_results.is_empty());
+        assert!(result.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn call_result_deadline() {
+        let inner = test_inner();
+        let result = Arc::new(Mutex::new(None));
+        let handle = lobby_created_handle(&inner, 4, &result);
+        let _other = lobby_created_handle(&inner, 5, &Arc::new(Mutex::new(None)));
+
+        let now = Instant::now();
+        handle.set_deadline(now + Duration::from_secs(10));
+        let expired = inner.callbacks.lock().unwrap().expired_call_results(now);
+        assert!(expired.is_empty());
+
+        let expired = inner
+            .callbacks
+            .lock()
+            .unwrap()
+            .expired_call_results(now + Duration::from_secs(10));
+        assert_eq!(expired.len(), 1);
+        for (api_call, cb) in expired {
+            assert_eq!(api_call, 4);
+            cb(Err(SteamError::Timeout));
+        }
+        assert_eq!(*result.lock().unwrap(), Some(Err(SteamError::Timeout)));
+        assert!(!handle.is_pending());
+        assert_eq!(inner.callbacks.lock().unwrap().call_results.len(), 1);
+    }
+
+    #[test]
+    fn subscribe_receiver() {
+        let inner = test_inner();
+        let receiver = unsafe { subscribe::<PersonaStateChange, _>(&inner) };
+        assert!(receiver.try_recv().is_none());
+
+        persona_change(&inner);
+        persona_change(&inner);
+        assert_eq!(receiver.try_iter().count(), 2);
+
+        drop(receiver);
+        assert!(inner.callbacks.lock().unwrap().callbacks.is_empty());
+    }
+
+    #[test]
+    fn subscribe_stream_wakes() {
+        let inner = test_inner();
+        let mut stream = unsafe { subscribe_stream::<PersonaStateChange, _>(&inner) };
+
+        let waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let task_waker = Waker::from(waker.clone());
+        let mut cx = Context::from_waker(&task_waker);
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+
+        persona_change(&inner);
+        assert_eq!(waker.0.load(Ordering::SeqCst), 1);
+        match Pin::new(&mut stream).poll_next(&mut cx) {
+            Poll::Ready(Some(p)) => assert_eq!(p.steam_id, SteamId(76561198040894045)),
+            _ => panic!("expected a queued callback"),
+        }
+
+        // Shutting down the registry ends the stream
+        inner.callbacks.lock().unwrap().callbacks.clear();
+        assert!(matches!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(None)
+        ));
+    }
+}
@@ -384,6 +384,55 @@ pub enum SteamError {
     WGNetworkSendExceeded,
 }
 
+/// Returned by `Client::init` and `Server::init` when the
+/// steamworks API fails to initialize.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[error("failed to init the steamworks API: {message}")]
+pub struct InitError {
+    /// Why initializing failed
+    pub reason: InitErrorReason,
+    /// A description of the failure that can be shown to the user
+    pub message: String,
+}
+
+impl InitError {
+    pub(crate) fn new(reason: InitErrorReason, message: impl Into<String>) -> InitError {
+        InitError {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<InitError> for SteamError {
+    fn from(_: InitError) -> SteamError {
+        SteamError::InitFailed
+    }
+}
+
+/// The reason the steamworks API failed to initialize
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InitErrorReason {
+    /// The steam api library couldn't be loaded or is missing
+    /// functions.
+    ///
+    /// Only returned with the `dynamic` feature.
+    LibraryNotLoaded,
+    /// The steam client isn't running
+    NoSteamClient,
+    /// The app ID of the game couldn't be determined
+    NoAppId,
+    /// Steam refused to initialize for any other reason.
+    ///
+    /// The bundled SDK doesn't report anything more specific, common
+    /// causes are the user not owning the game, the game running as a
+    /// different user than steam, an app ID that isn't completely set
+    /// up or a steam client that is older than the SDK.
+    Generic,
+}
+
 impl From<sys::EResult> for SteamError {
     fn from(r: sys::EResult) -> Self {
         match r {
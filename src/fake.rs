@@ -0,0 +1,1486 @@
+//! An in-process stand-in for the steam client.
+//!
+//! Enabling the `fake` feature swaps the flat steamworks functions used by the
+//! wrappers for an in-memory implementation, so `Client::init` succeeds without
+//! a running steam client and game code can be tested on a headless machine.
+//!
+//! The fake simulates:
+//!
+//! * the local user (`User`, `Friends::name`), their friends list and avatars
+//! * rich presence, invites and the overlay being opened and closed
+//! * lobbies (`Matchmaking`)
+//! * stats, achievements and leaderboards (`UserStats`)
+//! * cloud files (`RemoteStorage`)
+//! * workshop items, subscriptions and queries (`UGC`)
+//! * the basic `Apps` and `Utils` queries, and DLC
+//! * `NetworkingMessages` without any peers, nothing is received and sends
+//!   fail
+//!
+//! Callbacks and call results are queued and delivered through the normal
+//! `SingleClient::run_callbacks` path. The game server, input, networking
+//! sockets and networking utils interfaces are not simulated, their functions
+//! panic when called and their tests are skipped when the feature is enabled.
+//!
+//! The state is global to the process, tests using it should call [`reset`]
+//! first and should not run in parallel with each other.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use steamworks::*;
+//! steamworks::fake::reset();
+//! steamworks::fake::set_user(SteamId::from_raw(76561198040894045), "Tester");
+//! steamworks::fake::add_achievement("WIN_THE_GAME", false);
+//!
+//! let (client, single) = Client::init().unwrap();
+//! client.user_stats().achievement("WIN_THE_GAME").set().unwrap();
+//! single.run_callbacks();
+//! assert_eq!(steamworks::fake::achievement("WIN_THE_GAME"), Some(true));
+//! ```
+
+use super::*;
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::MutexGuard;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod sys;
+
+/// A workshop item known to the fake steam client
+#[derive(Clone, Debug)]
+pub struct WorkshopItem {
+    pub published_file_id: PublishedFileId,
+    pub consumer_app_id: Option<AppId>,
+    pub owner: Option<SteamId>,
+    pub title: String,
+    pub description: String,
+    pub preview_url: String,
+    pub metadata: String,
+    pub tags: Vec<String>,
+    pub key_value_tags: Vec<(String, String)>,
+    pub children: Vec<PublishedFileId>,
+    pub time_created: u32,
+    pub time_updated: u32,
+}
+
+impl Default for WorkshopItem {
+    fn default() -> Self {
+        WorkshopItem {
+            published_file_id: PublishedFileId(0),
+            consumer_app_id: None,
+            owner: None,
+            title: String::new(),
+            description: String::new(),
+            preview_url: String::new(),
+            metadata: String::new(),
+            tags: Vec::new(),
+            key_value_tags: Vec::new(),
+            children: Vec::new(),
+            time_created: 0,
+            time_updated: 0,
+        }
+    }
+}
+
+struct Raw {
+    id: i32,
+    // Stored as `u64`s to keep the callback structs aligned
+    data: Vec<u64>,
+    size: usize,
+}
+
+impl Raw {
+    fn new<T: Copy>(id: i32, value: &T) -> Raw {
+        let size = std::mem::size_of::<T>();
+        let mut data = vec![0u64; size.div_ceil(8)];
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                value as *const T as *const u8,
+                data.as_mut_ptr() as *mut u8,
+                size,
+            );
+        }
+        Raw { id, data, size }
+    }
+}
+
+struct Dlc {
+    app_id: u32,
+    name: String,
+    installed: bool,
+}
+
+struct Friend {
+    id: u64,
+    name: CString,
+    state: steamworks_sys::EPersonaState,
+    nickname: Option<CString>,
+    steam_level: i32,
+    name_history: Vec<CString>,
+    rich_presence: BTreeMap<CString, CString>,
+}
+
+struct Lobby {
+    id: u64,
+    owner: u64,
+    members: Vec<u64>,
+    member_limit: i32,
+    joinable: bool,
+    data: HashMap<String, CString>,
+}
+
+struct Leaderboard {
+    name: CString,
+    sort_method: steamworks_sys::ELeaderboardSortMethod,
+    display_type: steamworks_sys::ELeaderboardDisplayType,
+    entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Clone)]
+struct LeaderboardEntry {
+    user: u64,
+    score: i32,
+    details: Vec<i32>,
+}
+
+struct CloudFile {
+    data: Vec<u8>,
+    timestamp: i64,
+}
+
+struct Query {
+    items: Vec<u64>,
+    required_tags: Vec<String>,
+    excluded_tags: Vec<String>,
+    match_any_tag: bool,
+    total_only: bool,
+    only_ids: bool,
+    page: u32,
+}
+
+#[derive(Default)]
+struct ItemUpdate {
+    published_file_id: u64,
+    title: Option<String>,
+    description: Option<String>,
+    metadata: Option<String>,
+    tags: Option<Vec<String>>,
+    key_value_tags: Vec<(String, String)>,
+    removed_key_value_tags: Vec<String>,
+    remove_all_key_value_tags: bool,
+}
+
+struct State {
+    app_id: u32,
+    user: u64,
+    user_name: CString,
+    persona_state: steamworks_sys::EPersonaState,
+    user_restrictions: u32,
+    steam_level: i32,
+    dlcs: Vec<Dlc>,
+    purchase_time: u32,
+    family_shared: bool,
+    timed_trial: Option<(u32, u32)>,
+    installed_depots: Vec<u32>,
+    file_details: HashMap<String, FileDetails>,
+    steam_deck: bool,
+    big_picture: bool,
+    battery_power: Option<u8>,
+    gamepad_text_input: Option<String>,
+    entered_gamepad_text: Option<CString>,
+    floating_text_input: bool,
+    filtered_words: Vec<String>,
+    images: HashMap<i32, SteamImage>,
+    avatars: HashMap<u64, i32>,
+    achievement_icons: HashMap<String, i32>,
+    launch_command_line: String,
+    launch_query_params: HashMap<String, CString>,
+    friends: Vec<Friend>,
+    rich_presence: HashMap<String, String>,
+    overlay: Option<String>,
+    invites: Vec<(u64, String)>,
+    lobbies: Vec<Lobby>,
+    stats_i32: HashMap<String, i32>,
+    stats_f32: HashMap<String, f32>,
+    achievements: HashMap<String, bool>,
+    stats_stored: bool,
+    leaderboards: Vec<Leaderboard>,
+    downloaded_entries: HashMap<u64, Vec<(i32, LeaderboardEntry)>>,
+    cloud_enabled_for_account: bool,
+    cloud_enabled_for_app: bool,
+    files: BTreeMap<CString, CloudFile>,
+    write_streams: HashMap<u64, (CString, Vec<u8>)>,
+    reads: HashMap<u64, Vec<u8>>,
+    workshop_items: BTreeMap<u64, WorkshopItem>,
+    subscribed_items: BTreeSet<u64>,
+    queries: HashMap<u64, Query>,
+    item_updates: HashMap<u64, ItemUpdate>,
+    queue: VecDeque<Raw>,
+    current: Option<Raw>,
+    call_results: HashMap<u64, Raw>,
+    next_handle: u64,
+}
+
+impl State {
+    fn new() -> State {
+        State {
+            app_id: 480,
+            user: 76561197960287930,
+            user_name: CString::new("Player").unwrap(),
+            persona_state: steamworks_sys::EPersonaState::k_EPersonaStateOnline,
+            user_restrictions: 0,
+            steam_level: 1,
+            dlcs: Vec::new(),
+            purchase_time: now(),
+            family_shared: false,
+            timed_trial: None,
+            installed_depots: Vec::new(),
+            file_details: HashMap::new(),
+            steam_deck: false,
+            big_picture: false,
+            battery_power: None,
+            gamepad_text_input: None,
+            entered_gamepad_text: None,
+            floating_text_input: false,
+            filtered_words: Vec::new(),
+            images: HashMap::new(),
+            avatars: HashMap::new(),
+            achievement_icons: HashMap::new(),
+            launch_command_line: String::new(),
+            launch_query_params: HashMap::new(),
+            friends: Vec::new(),
+            rich_presence: HashMap::new(),
+            overlay: None,
+            invites: Vec::new(),
+            lobbies: Vec::new(),
+            stats_i32: HashMap::new(),
+            stats_f32: HashMap::new(),
+            achievements: HashMap::new(),
+            stats_stored: false,
+            leaderboards: Vec::new(),
+            downloaded_entries: HashMap::new(),
+            cloud_enabled_for_account: true,
+            cloud_enabled_for_app: true,
+            files: BTreeMap::new(),
+            write_streams: HashMap::new(),
+            reads: HashMap::new(),
+            workshop_items: BTreeMap::new(),
+            subscribed_items: BTreeSet::new(),
+            queries: HashMap::new(),
+            item_updates: HashMap::new(),
+            queue: VecDeque::new(),
+            current: None,
+            call_results: HashMap::new(),
+            next_handle: 1,
+        }
+    }
+
+    /// Opens the overlay on the given dialog, posting
+    /// `GameOverlayActivated` if it was closed.
+    fn open_overlay(&mut self, dialog: String) {
+        if self.overlay.replace(dialog).is_none() {
+            self.post(
+                steamworks_sys::GameOverlayActivated_t_k_iCallback as i32,
+                &steamworks_sys::GameOverlayActivated_t { m_bActive: 1 },
+            );
+        }
+    }
+
+    fn next_handle(&mut self) -> u64 {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        handle
+    }
+
+    fn post<T: Copy>(&mut self, id: i32, value: &T) {
+        self.queue.push_back(Raw::new(id, value));
+    }
+
+    /// Completes a new api call with the given result and queues the
+    /// `SteamAPICallCompleted_t` that `run_callbacks` looks for.
+    fn complete<T: Copy>(&mut self, id: i32, value: &T) -> u64 {
+        let api_call = self.next_handle();
+        self.complete_call(api_call, id, value);
+        api_call
+    }
+
+    fn complete_call<T: Copy>(&mut self, api_call: u64, id: i32, value: &T) {
+        self.call_results.insert(api_call, Raw::new(id, value));
+        self.post(
+            steamworks_sys::SteamAPICallCompleted_t_k_iCallback as i32,
+            &steamworks_sys::SteamAPICallCompleted_t {
+                m_hAsyncCall: api_call,
+                m_iCallback: id,
+                m_cubParam: std::mem::size_of::<T>() as u32,
+            },
+        );
+    }
+}
+
+lazy_static! {
+    static ref STATE: Mutex<State> = Mutex::new(State::new());
+}
+
+fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn now() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+/// Clears every piece of simulated state, including pending callbacks.
+pub fn reset() {
+    *state() = State::new();
+}
+
+/// Sets the app id reported by `Utils::app_id`. Defaults to 480 (Spacewar).
+pub fn set_app_id(app_id: AppId) {
+    state().app_id = app_id.0;
+}
+
+/// Sets the steam id and persona name of the local user.
+pub fn set_user(steam_id: SteamId, name: &str) {
+    let mut state = state();
+    state.user = steam_id.0;
+    state.user_name = CString::new(name).unwrap();
+}
+
+/// Sets whether steam reports running on a Steam Deck and in Big
+/// Picture mode.
+pub fn set_steam_deck(steam_deck: bool, big_picture: bool) {
+    let mut state = state();
+    state.steam_deck = steam_deck;
+    state.big_picture = big_picture;
+}
+
+/// Sets the battery power in percent, or `None` for AC power.
+///
+/// Posts `LowBatteryPower` when the battery is nearly empty, with a
+/// rough estimate of the minutes left.
+pub fn set_battery_power(power: Option<u8>) {
+    let mut state = state();
+    state.battery_power = power;
+    if let Some(power @ 0..=5) = power {
+        state.post(
+            steamworks_sys::LowBatteryPower_t_k_iCallback as i32,
+            &steamworks_sys::LowBatteryPower_t {
+                m_nMinutesBatteryLeft: power * 2,
+            },
+        );
+    }
+}
+
+/// Returns the existing text of the open gamepad text input dialog, if
+/// one is open.
+pub fn gamepad_text_input() -> Option<String> {
+    state().gamepad_text_input.clone()
+}
+
+/// Closes the gamepad text input dialog as if the user submitted `text`,
+/// or cancelled it if `None`.
+pub fn submit_gamepad_text_input(text: Option<&str>) {
+    let mut state = state();
+    if state.gamepad_text_input.take().is_none() {
+        return;
+    }
+    state.entered_gamepad_text = text.map(|t| CString::new(t).unwrap());
+    let submitted = state.entered_gamepad_text.as_ref();
+    let value = steamworks_sys::GamepadTextInputDismissed_t {
+        m_bSubmitted: submitted.is_some(),
+        m_unSubmittedText: submitted.map_or(0, |t| t.as_bytes().len() as u32),
+    };
+    state.post(
+        steamworks_sys::GamepadTextInputDismissed_t_k_iCallback as i32,
+        &value,
+    );
+}
+
+/// Returns whether the floating on-screen keyboard is open.
+pub fn floating_gamepad_text_input() -> bool {
+    state().floating_text_input
+}
+
+/// Adds a word that `Utils::filter_text` replaces with `*`s.
+pub fn add_filtered_word(word: &str) {
+    state().filtered_words.push(word.to_owned());
+}
+
+/// Sets the steam level of the local user.
+pub fn set_steam_level(level: u32) {
+    state().steam_level = level as i32;
+}
+
+/// Sets whether the current app is borrowed through family sharing.
+pub fn set_family_shared(family_shared: bool) {
+    state().family_shared = family_shared;
+}
+
+/// Makes the current app a timed trial, or a full purchase if `None`.
+pub fn set_timed_trial(trial: Option<TimedTrial>) {
+    state().timed_trial = trial.map(|t| (t.seconds_allowed, t.seconds_played));
+}
+
+/// Sets the depots of the current app that are installed.
+pub fn set_installed_depots(depots: &[DepotId]) {
+    state().installed_depots = depots.iter().map(|d| d.0).collect();
+}
+
+/// Sets the details returned by `Apps::file_details` for a file in the
+/// app's depots.
+pub fn add_file_details(path: &str, details: FileDetails) {
+    state().file_details.insert(path.to_owned(), details);
+}
+
+/// Adds a DLC owned by the local user to the current app.
+///
+/// DLC that isn't installed can be installed with `Apps::install_dlc`.
+pub fn add_dlc(app_id: AppId, name: &str, installed: bool) {
+    state().dlcs.push(Dlc {
+        app_id: app_id.0,
+        name: name.to_owned(),
+        installed,
+    });
+}
+
+/// Returns whether a DLC is installed, if it exists.
+pub fn dlc_installed(app_id: AppId) -> Option<bool> {
+    state()
+        .dlcs
+        .iter()
+        .find(|d| d.app_id == app_id.0)
+        .map(|d| d.installed)
+}
+
+/// Sets the launch command line and query parameters, as if the game was
+/// started through a `steam://run` link.
+///
+/// Posts `NewUrlLaunchParameters` like steam does when the game is
+/// already running.
+pub fn set_launch_parameters(command_line: &str, query_params: &[(&str, &str)]) {
+    let mut state = state();
+    state.launch_command_line = command_line.to_owned();
+    state.launch_query_params = query_params
+        .iter()
+        .map(|&(key, value)| (key.to_owned(), CString::new(value).unwrap()))
+        .collect();
+    state.post(
+        steamworks_sys::NewUrlLaunchParameters_t_k_iCallback as i32,
+        &steamworks_sys::NewUrlLaunchParameters_t { _address: 0 },
+    );
+}
+
+/// Adds an image and returns the handle `Utils::image` loads it from.
+pub fn add_image(image: SteamImage) -> i32 {
+    let mut state = state();
+    let handle = state.next_handle() as i32;
+    state.images.insert(handle, image);
+    handle
+}
+
+/// Sets the avatar of a user, used for all avatar sizes.
+pub fn set_avatar(steam_id: SteamId, image: SteamImage) {
+    let handle = add_image(image);
+    state().avatars.insert(steam_id.0, handle);
+}
+
+/// Marks the avatar of a user as being downloaded, the avatar getters
+/// return -1 until `load_avatar` is called.
+pub fn set_avatar_loading(steam_id: SteamId) {
+    state().avatars.insert(steam_id.0, -1);
+}
+
+/// Sets the avatar of a user like `set_avatar` and posts
+/// `AvatarImageLoaded`, as steam does once an avatar has been fetched.
+pub fn load_avatar(steam_id: SteamId, image: SteamImage) {
+    let loaded = steamworks_sys::AvatarImageLoaded_t {
+        m_steamID: steamworks_sys::CSteamID {
+            m_steamid: steamworks_sys::CSteamID_SteamID_t {
+                m_unAll64Bits: steam_id.0,
+            },
+        },
+        m_iImage: 0,
+        m_iWide: image.width as i32,
+        m_iTall: image.height as i32,
+    };
+    let handle = add_image(image);
+    let mut state = state();
+    state.avatars.insert(steam_id.0, handle);
+    state.post(
+        steamworks_sys::AvatarImageLoaded_t_k_iCallback as i32,
+        &steamworks_sys::AvatarImageLoaded_t {
+            m_iImage: handle,
+            ..loaded
+        },
+    );
+}
+
+/// Sets the icon of an achievement.
+pub fn set_achievement_icon(name: &str, image: SteamImage) {
+    let handle = add_image(image);
+    state().achievement_icons.insert(name.to_owned(), handle);
+}
+
+/// Adds an immediate friend to the local user's friends list.
+pub fn add_friend(steam_id: SteamId, name: &str, friend_state: FriendState) {
+    state().friends.push(Friend {
+        id: steam_id.0,
+        name: CString::new(name).unwrap(),
+        state: persona_state(friend_state),
+        nickname: None,
+        steam_level: 0,
+        name_history: Vec::new(),
+        rich_presence: BTreeMap::new(),
+    });
+}
+
+fn persona_state(friend_state: FriendState) -> steamworks_sys::EPersonaState {
+    use steamworks_sys::EPersonaState::*;
+    match friend_state {
+        FriendState::Offline => k_EPersonaStateOffline,
+        FriendState::Online => k_EPersonaStateOnline,
+        FriendState::Busy => k_EPersonaStateBusy,
+        FriendState::Away => k_EPersonaStateAway,
+        FriendState::Snooze => k_EPersonaStateSnooze,
+        FriendState::LookingToTrade => k_EPersonaStateLookingToTrade,
+        FriendState::LookingToPlay => k_EPersonaStateLookingToPlay,
+        FriendState::Invisible => k_EPersonaStateInvisible,
+    }
+}
+
+/// Sets the persona state of the local user. Defaults to online.
+pub fn set_persona_state(friend_state: FriendState) {
+    state().persona_state = persona_state(friend_state);
+}
+
+/// Sets the restrictions reported by `Friends::user_restrictions`.
+pub fn set_user_restrictions(restrictions: UserRestrictions) {
+    state().user_restrictions = restrictions.bits();
+}
+
+/// Sets or unsets the nickname the local user has given a friend.
+pub fn set_friend_nickname(friend: SteamId, nickname: Option<&str>) {
+    if let Some(friend) = state().friends.iter_mut().find(|f| f.id == friend.0) {
+        friend.nickname = nickname.map(|n| CString::new(n).unwrap());
+    }
+}
+
+/// Sets the steam level of a friend.
+pub fn set_friend_steam_level(friend: SteamId, level: u32) {
+    if let Some(friend) = state().friends.iter_mut().find(|f| f.id == friend.0) {
+        friend.steam_level = level as i32;
+    }
+}
+
+/// Sets the previous persona names of a friend, most recent first.
+pub fn set_friend_name_history(friend: SteamId, names: &[&str]) {
+    if let Some(friend) = state().friends.iter_mut().find(|f| f.id == friend.0) {
+        friend.name_history = names.iter().map(|n| CString::new(*n).unwrap()).collect();
+    }
+}
+
+/// Returns the rich presence value the local user has set for the key.
+pub fn rich_presence(key: &str) -> Option<String> {
+    state().rich_presence.get(key).cloned()
+}
+
+/// Sets or unsets a rich presence key of a friend and posts
+/// `FriendRichPresenceUpdate`.
+pub fn set_friend_rich_presence(friend: SteamId, key: &str, value: Option<&str>) {
+    let mut state = state();
+    let app_id = state.app_id;
+    let friend = match state.friends.iter_mut().find(|f| f.id == friend.0) {
+        Some(friend) => friend,
+        None => return,
+    };
+    let key = CString::new(key).unwrap();
+    match value {
+        Some(value) if !value.is_empty() => {
+            friend
+                .rich_presence
+                .insert(key, CString::new(value).unwrap());
+        }
+        _ => {
+            friend.rich_presence.remove(&key);
+        }
+    }
+    let update = rich_presence_update(friend.id, app_id);
+    state.post(
+        steamworks_sys::FriendRichPresenceUpdate_t_k_iCallback as i32,
+        &update,
+    );
+}
+
+fn rich_presence_update(friend: u64, app_id: u32) -> steamworks_sys::FriendRichPresenceUpdate_t {
+    steamworks_sys::FriendRichPresenceUpdate_t {
+        m_steamIDFriend: steamworks_sys::CSteamID {
+            m_steamid: steamworks_sys::CSteamID_SteamID_t {
+                m_unAll64Bits: friend,
+            },
+        },
+        m_nAppID: app_id,
+    }
+}
+
+/// Returns the dialog the overlay was last opened on, if it is open.
+///
+/// Dialogs about a user are reported as `<dialog>:<steam id>`, the store
+/// as `Store:<app id>`, web pages as their url and invite dialogs as
+/// `LobbyInvite:<lobby>`, `RemotePlayTogetherInvite:<lobby>` and
+/// `Invite:<connect string>`.
+pub fn overlay() -> Option<String> {
+    state().overlay.clone()
+}
+
+/// Closes the overlay, posting `GameOverlayActivated` if it was open.
+pub fn close_overlay() {
+    let mut state = state();
+    if state.overlay.take().is_some() {
+        state.post(
+            steamworks_sys::GameOverlayActivated_t_k_iCallback as i32,
+            &steamworks_sys::GameOverlayActivated_t { m_bActive: 0 },
+        );
+    }
+}
+
+/// Returns the friends invited with `Friends::invite_user_to_game` and
+/// the connect strings they were sent.
+pub fn invites() -> Vec<(SteamId, String)> {
+    state()
+        .invites
+        .iter()
+        .map(|(id, connect)| (SteamId(*id), connect.clone()))
+        .collect()
+}
+
+/// Posts `GameRichPresenceJoinRequested` as if the user accepted an
+/// invite from `friend` or joined them from the friends list.
+pub fn request_rich_presence_join(friend: SteamId, connect: &str) {
+    let mut value = steamworks_sys::GameRichPresenceJoinRequested_t {
+        m_steamIDFriend: steamworks_sys::CSteamID {
+            m_steamid: steamworks_sys::CSteamID_SteamID_t {
+                m_unAll64Bits: friend.0,
+            },
+        },
+        m_rgchConnect: [0; 256],
+    };
+    for (dest, &src) in value
+        .m_rgchConnect
+        .iter_mut()
+        .zip(connect.as_bytes().iter().take(255))
+    {
+        *dest = src as std::os::raw::c_char;
+    }
+    state().post(
+        steamworks_sys::GameRichPresenceJoinRequested_t_k_iCallback as i32,
+        &value,
+    );
+}
+
+/// Adds a lobby owned by `owner` that is returned by lobby list requests
+/// and can be joined.
+pub fn add_lobby(owner: SteamId, member_limit: u32) -> LobbyId {
+    let mut state = state();
+    let id = LobbyId(0x0186_0000_0000_0000 | state.next_handle());
+    state.lobbies.push(Lobby {
+        id: id.0,
+        owner: owner.0,
+        members: vec![owner.0],
+        member_limit: member_limit as i32,
+        joinable: true,
+        data: HashMap::new(),
+    });
+    id
+}
+
+/// Sets a metadata value on a lobby.
+pub fn set_lobby_data(lobby: LobbyId, key: &str, value: &str) {
+    if let Some(lobby) = state().lobbies.iter_mut().find(|l| l.id == lobby.0) {
+        lobby
+            .data
+            .insert(key.to_owned(), CString::new(value).unwrap());
+    }
+}
+
+/// Returns the members of a lobby, if it exists.
+pub fn lobby_members(lobby: LobbyId) -> Option<Vec<SteamId>> {
+    state()
+        .lobbies
+        .iter()
+        .find(|l| l.id == lobby.0)
+        .map(|l| l.members.iter().map(|&id| SteamId(id)).collect())
+}
+
+/// Defines an integer stat with its current value.
+pub fn add_stat_i32(name: &str, value: i32) {
+    state().stats_i32.insert(name.to_owned(), value);
+}
+
+/// Defines a float stat with its current value.
+pub fn add_stat_f32(name: &str, value: f32) {
+    state().stats_f32.insert(name.to_owned(), value);
+}
+
+/// Defines an achievement and whether it is unlocked.
+pub fn add_achievement(name: &str, achieved: bool) {
+    state().achievements.insert(name.to_owned(), achieved);
+}
+
+/// Returns the current value of an integer stat.
+pub fn stat_i32(name: &str) -> Option<i32> {
+    state().stats_i32.get(name).copied()
+}
+
+/// Returns the current value of a float stat.
+pub fn stat_f32(name: &str) -> Option<f32> {
+    state().stats_f32.get(name).copied()
+}
+
+/// Returns whether an achievement is unlocked.
+pub fn achievement(name: &str) -> Option<bool> {
+    state().achievements.get(name).copied()
+}
+
+/// Returns whether `UserStats::store_stats` has been called since the
+/// stats were last changed.
+pub fn stats_stored() -> bool {
+    state().stats_stored
+}
+
+/// Adds a file to the local user's steam cloud.
+pub fn add_cloud_file(name: &str, data: &[u8]) {
+    state().files.insert(
+        CString::new(name).unwrap(),
+        CloudFile {
+            data: data.to_vec(),
+            timestamp: now() as i64,
+        },
+    );
+}
+
+/// Returns the contents of a file in the local user's steam cloud.
+pub fn cloud_file(name: &str) -> Option<Vec<u8>> {
+    let name = CString::new(name).unwrap();
+    state().files.get(&name).map(|f| f.data.clone())
+}
+
+/// Adds a workshop item, returning its id.
+///
+/// A new id is allocated if the item's `published_file_id` is zero.
+pub fn add_workshop_item(mut item: WorkshopItem) -> PublishedFileId {
+    let mut state = state();
+    if item.published_file_id.0 == 0 {
+        item.published_file_id = PublishedFileId(state.next_handle());
+    }
+    let id = item.published_file_id;
+    state.workshop_items.insert(id.0, item);
+    id
+}
+
+/// Returns a workshop item, if it exists.
+pub fn workshop_item(id: PublishedFileId) -> Option<WorkshopItem> {
+    state().workshop_items.get(&id.0).cloned()
+}
+
+/// Subscribes the local user to a workshop item.
+pub fn subscribe_workshop_item(id: PublishedFileId) {
+    state().subscribed_items.insert(id.0);
+}
+
+/// Queues a raw callback to be delivered by the next `run_callbacks`.
+///
+/// # Safety
+///
+/// `value` must be the `sys` struct that matches the callback `id`.
+pub unsafe fn post_raw_callback<T: Copy>(id: i32, value: T) {
+    state().post(id, &value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::future::Future;
+    use std::io::{Read, Write};
+    use std::pin::Pin;
+    use std::sync::mpsc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    fn init() -> (Client, SingleClient) {
+        reset();
+        set_user(SteamId(76561198040894045), "Tester");
+        Client::init().unwrap()
+    }
+
+    /// Polls a future once, the fake never needs its waker
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        struct NoopWaker;
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = Waker::from(Arc::new(NoopWaker));
+        future.poll(&mut Context::from_waker(&waker))
+    }
+
+    #[test]
+    #[serial]
+    fn user_and_friends() {
+        let (client, _single) = init();
+        add_friend(SteamId(76561198174976054), "Friend", FriendState::Online);
+
+        assert_eq!(client.user().steam_id(), SteamId(76561198040894045));
+        assert_eq!(client.friends().name(), "Tester");
+
+        let friends = client.friends().get_friends(FriendFlags::IMMEDIATE);
+        assert_eq!(friends.len(), 1);
+        assert_eq!(friends[0].name(), "Friend");
+        assert_eq!(friends[0].state(), FriendState::Online);
+    }
+
+    #[test]
+    #[serial]
+    fn dlc() {
+        let (client, single) = init();
+        add_dlc(AppId(1001), "Soundtrack", true);
+        add_dlc(AppId(1002), "Expansion", false);
+
+        let apps = client.apps();
+        assert_eq!(apps.dlc_count(), 2);
+        let dlcs = apps.dlcs();
+        assert_eq!(dlcs[1].app_id, AppId(1002));
+        assert_eq!(dlcs[1].name, "Expansion");
+        assert!(dlcs[1].available);
+        assert!(apps.dlc(2).is_none());
+        assert!(apps.is_dlc_installed(AppId(1001)));
+        assert!(!apps.is_dlc_installed(AppId(1002)));
+
+        let installed = client.subscribe::<DlcInstalled>();
+        apps.install_dlc(AppId(1002));
+        single.run_callbacks();
+        assert_eq!(installed.try_recv().unwrap().app_id, AppId(1002));
+        assert_eq!(dlc_installed(AppId(1002)), Some(true));
+
+        apps.uninstall_dlc(AppId(1002));
+        assert!(!apps.is_dlc_installed(AppId(1002)));
+        assert_eq!(apps.dlc_download_progress(AppId(1002)), None);
+    }
+
+    #[test]
+    #[serial]
+    fn launch_parameters() {
+        let (client, single) = init();
+        let apps = client.apps();
+        assert_eq!(apps.launch_command_line(), "");
+        assert!(apps.launch_commands().is_empty());
+
+        let changed = client.subscribe::<NewUrlLaunchParameters>();
+        set_launch_parameters("+connect 10.0.0.2:27015", &[("@mode", "coop")]);
+        single.run_callbacks();
+        assert!(changed.try_recv().is_some());
+        assert_eq!(
+            apps.launch_commands().connect.as_deref(),
+            Some("10.0.0.2:27015")
+        );
+        assert_eq!(apps.launch_query_param("@mode").as_deref(), Some("coop"));
+        assert_eq!(apps.launch_query_param("@missing"), None);
+
+        // Longer than the initial buffer
+        let long = format!("+password {} +connect 10.0.0.3:27015", "x".repeat(2000));
+        set_launch_parameters(&long, &[]);
+        assert_eq!(apps.launch_command_line(), long);
+        assert_eq!(
+            apps.launch_commands().connect.as_deref(),
+            Some("10.0.0.3:27015")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn ownership() {
+        let (client, _single) = init();
+        let apps = client.apps();
+        assert!(apps.earliest_purchase_time(AppId(480)).is_some());
+        assert_eq!(apps.earliest_purchase_time(AppId(481)), None);
+        assert!(!apps.is_subscribed_from_family_sharing());
+        assert_eq!(apps.timed_trial(), None);
+        assert!(apps.installed_depots(AppId(480)).is_empty());
+
+        set_family_shared(true);
+        set_timed_trial(Some(TimedTrial {
+            seconds_allowed: 3600,
+            seconds_played: 600,
+        }));
+        set_installed_depots(&[DepotId(481), DepotId(482)]);
+        assert!(apps.is_subscribed_from_family_sharing());
+        let trial = apps.timed_trial().unwrap();
+        assert_eq!(trial.seconds_remaining(), 3000);
+        assert!(!trial.is_expired());
+        assert_eq!(
+            apps.installed_depots(AppId(480)),
+            vec![DepotId(481), DepotId(482)]
+        );
+
+        // More than fit in the initial buffer
+        let depots: Vec<_> = (1000..1100).map(DepotId).collect();
+        set_installed_depots(&depots);
+        assert_eq!(apps.installed_depots(AppId(480)), depots);
+    }
+
+    #[test]
+    #[serial]
+    fn file_details() {
+        let (client, single) = init();
+        let details = FileDetails {
+            size: 5,
+            sha1: [0xab; 20],
+            flags: 0,
+        };
+        add_file_details("data/level1.pak", details);
+
+        let (tx, rx) = mpsc::channel();
+        let tx2 = tx.clone();
+        let apps = client.apps();
+        apps.file_details("data/level1.pak", move |res| tx.send(res).unwrap());
+        apps.file_details("data/missing.pak", move |res| tx2.send(res).unwrap());
+        single.run_callbacks();
+        let found = rx.try_recv().unwrap().unwrap();
+        assert_eq!(found, details);
+        assert!(found.matches(5, &[0xab; 20]));
+        assert_eq!(found.sha1_hex(), "ab".repeat(20));
+        assert_eq!(rx.try_recv().unwrap(), Err(SteamError::FileNotFound));
+
+        let mut future = Box::pin(apps.file_details_async("data/level1.pak"));
+        assert!(poll_once(future.as_mut()).is_pending());
+        single.run_callbacks();
+        assert_eq!(poll_once(future.as_mut()), Poll::Ready(Ok(details)));
+    }
+
+    #[test]
+    #[serial]
+    fn environment() {
+        let (client, single) = init();
+        let utils = client.utils();
+        assert!(!utils.is_steam_running_on_steam_deck());
+        assert_eq!(utils.current_battery_power(), None);
+
+        set_steam_deck(true, true);
+        assert!(utils.is_steam_running_on_steam_deck());
+        assert!(utils.is_steam_in_big_picture_mode());
+
+        let low = client.subscribe::<LowBatteryPower>();
+        set_battery_power(Some(50));
+        single.run_callbacks();
+        assert_eq!(utils.current_battery_power(), Some(50));
+        assert!(low.try_recv().is_none());
+
+        set_battery_power(Some(4));
+        single.run_callbacks();
+        assert_eq!(low.try_recv().unwrap().minutes_left, 8);
+    }
+
+    #[test]
+    #[serial]
+    fn gamepad_text_input_dialogs() {
+        let (client, single) = init();
+        let utils = client.utils();
+        let dismissed = client.subscribe::<GamepadTextInputDismissed>();
+
+        assert!(utils.show_gamepad_text_input(
+            GamepadTextInputMode::Normal,
+            GamepadTextInputLineMode::SingleLine,
+            "Lobby name",
+            32,
+            "My lobby",
+        ));
+        assert_eq!(gamepad_text_input().as_deref(), Some("My lobby"));
+        submit_gamepad_text_input(Some("Deck lobby"));
+        single.run_callbacks();
+        let text = dismissed.try_recv().unwrap().text;
+        assert_eq!(text.as_deref(), Some("Deck lobby"));
+        assert_eq!(utils.entered_gamepad_text_input(), text);
+
+        utils.show_gamepad_text_input(
+            GamepadTextInputMode::Password,
+            GamepadTextInputLineMode::SingleLine,
+            "Password",
+            32,
+            "",
+        );
+        submit_gamepad_text_input(None);
+        single.run_callbacks();
+        assert_eq!(dismissed.try_recv().unwrap().text, None);
+
+        let floating = client.subscribe::<FloatingGamepadTextInputDismissed>();
+        assert!(utils.show_floating_gamepad_text_input(
+            FloatingGamepadTextInputMode::SingleLine,
+            0,
+            0,
+            200,
+            20,
+        ));
+        assert!(floating_gamepad_text_input());
+        assert!(utils.dismiss_floating_gamepad_text_input());
+        single.run_callbacks();
+        assert!(floating.try_recv().is_some());
+        assert!(!floating_gamepad_text_input());
+    }
+
+    #[test]
+    #[serial]
+    fn filter_text() {
+        let (client, _single) = init();
+        add_filtered_word("heck");
+        let utils = client.utils();
+        assert!(utils.init_filter_text());
+        let friend = SteamId(76561198174976054);
+        assert_eq!(
+            utils.filter_text(TextFilteringContext::Chat, friend, "what the heck"),
+            "what the ****"
+        );
+        assert_eq!(
+            utils.filter_text(TextFilteringContext::Name, friend, "Lobby"),
+            "Lobby"
+        );
+        assert_eq!(
+            utils.filter_text(TextFilteringContext::Chat, friend, ""),
+            ""
+        );
+        // Steam only sees the text up to a nul
+        assert_eq!(
+            utils.filter_text(TextFilteringContext::Chat, friend, "heck\0hidden"),
+            "****"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn images() {
+        let (client, _single) = init();
+        let friend = SteamId(76561198174976054);
+        add_friend(friend, "Friend", FriendState::Online);
+        let avatar = SteamImage {
+            width: 2,
+            height: 1,
+            rgba: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        set_avatar(friend, avatar.clone());
+        add_achievement("WIN_THE_GAME", false);
+
+        let friend = client.friends().get_friend(friend);
+        assert_eq!(friend.small_avatar(), Some(avatar.clone()));
+        assert_eq!(friend.large_avatar(), Some(avatar.clone()));
+        assert_eq!(
+            client.friends().get_friend(SteamId(1)).medium_avatar(),
+            None
+        );
+
+        let stats = client.user_stats();
+        assert_eq!(stats.achievement("WIN_THE_GAME").icon(), None);
+        set_achievement_icon("WIN_THE_GAME", avatar.clone());
+        assert_eq!(
+            stats.achievement("WIN_THE_GAME").icon(),
+            Some(avatar.clone())
+        );
+
+        let handle = add_image(avatar.clone());
+        assert_eq!(client.utils().image(handle), Some(avatar));
+        assert_eq!(client.utils().image(0), None);
+        assert_eq!(client.utils().image(-1), None);
+    }
+
+    #[test]
+    #[serial]
+    fn friend_rich_presence() {
+        let (client, single) = init();
+        let friend_id = SteamId(76561198174976054);
+        add_friend(friend_id, "Friend", FriendState::Online);
+        let friends = client.friends();
+        let friend = friends.get_friend(friend_id);
+        assert_eq!(friend.rich_presence("status"), None);
+        assert!(friend.rich_presence_keys().is_empty());
+
+        let updates = client.subscribe::<FriendRichPresenceUpdate>();
+        set_friend_rich_presence(friend_id, "steam_display", Some("#InMatch"));
+        set_friend_rich_presence(friend_id, "steam_player_group_size", Some("3"));
+        set_friend_rich_presence(friend_id, "connect", Some("+connect 10.0.0.2:27015"));
+        set_friend_rich_presence(friend_id, "map", Some("X"));
+        single.run_callbacks();
+        let update = updates.try_recv().unwrap();
+        assert_eq!(update.friend, friend_id);
+        assert_eq!(update.app_id, AppId(480));
+
+        assert_eq!(friend.rich_presence("map").as_deref(), Some("X"));
+        assert_eq!(
+            friend.rich_presence_keys(),
+            vec!["connect", "map", "steam_display", "steam_player_group_size"]
+        );
+        let info = friend.rich_presence_info();
+        assert_eq!(info.display.as_deref(), Some("#InMatch"));
+        assert_eq!(info.player_group_size, Some(3));
+        assert_eq!(info.player_group, None);
+        assert_eq!(
+            info.launch_commands().connect.as_deref(),
+            Some("10.0.0.2:27015")
+        );
+
+        friends.request_friend_rich_presence(friend_id);
+        single.run_callbacks();
+        assert!(updates.try_recv().is_some());
+
+        assert!(friends.set_rich_presence_info(&RichPresence {
+            status: Some("In a match".into()),
+            player_group_size: Some(2),
+            ..Default::default()
+        }));
+        assert_eq!(rich_presence("status").as_deref(), Some("In a match"));
+        assert_eq!(
+            rich_presence("steam_player_group_size").as_deref(),
+            Some("2")
+        );
+        friends.clear_rich_presence();
+        assert_eq!(rich_presence("status"), None);
+    }
+
+    #[test]
+    #[serial]
+    fn invites_and_joins() {
+        let (client, single) = init();
+        let friend = SteamId(76561198174976054);
+        add_friend(friend, "Friend", FriendState::Online);
+        let friends = client.friends();
+
+        let overlay_events = client.subscribe::<GameOverlayActivated>();
+        friends.activate_invite_dialog_connect_string("+connect 10.0.0.2:27015");
+        assert_eq!(overlay().as_deref(), Some("Invite:+connect 10.0.0.2:27015"));
+        close_overlay();
+        single.run_callbacks();
+        assert!(overlay_events.try_recv().unwrap().active);
+        assert!(!overlay_events.try_recv().unwrap().active);
+
+        assert!(friends.invite_user_to_game(friend, "+connect 10.0.0.2:27015"));
+        assert!(!friends.invite_user_to_game(SteamId(1), "+connect 10.0.0.2:27015"));
+        assert_eq!(
+            invites(),
+            vec![(friend, "+connect 10.0.0.2:27015".to_owned())]
+        );
+
+        let joins = client.subscribe::<GameRichPresenceJoinRequested>();
+        request_rich_presence_join(friend, "+connect 10.0.0.3:27015 +password pw");
+        single.run_callbacks();
+        let join = joins.try_recv().unwrap();
+        assert_eq!(join.friend, friend);
+        let commands = join.launch_commands();
+        assert_eq!(commands.connect.as_deref(), Some("10.0.0.3:27015"));
+        assert_eq!(commands.password.as_deref(), Some("pw"));
+    }
+
+    #[test]
+    #[serial]
+    fn overlay_dialogs() {
+        let (client, single) = init();
+        let friends = client.friends();
+        let friend = SteamId(76561198174976054);
+        let overlay_events = client.subscribe::<GameOverlayActivated>();
+
+        friends.activate_game_overlay(OverlayDialog::OfficialGameGroup);
+        assert_eq!(overlay().as_deref(), Some("officialgamegroup"));
+        friends.activate_game_overlay_to_user(OverlayUserDialog::FriendAdd, friend);
+        assert_eq!(overlay().as_deref(), Some("friendadd:76561198174976054"));
+        friends.activate_game_overlay_to_store(AppId(480), OverlayToStoreFlag::AddToCart);
+        assert_eq!(overlay().as_deref(), Some("Store:480"));
+        friends.activate_game_overlay_to_web_page("https://example.com", OverlayWebPageMode::Modal);
+        assert_eq!(overlay().as_deref(), Some("https://example.com"));
+        let lobby = add_lobby(friend, 4);
+        friends.activate_remote_play_together_invite_dialog(lobby);
+        assert_eq!(
+            overlay(),
+            Some(format!("RemotePlayTogetherInvite:{}", lobby.raw()))
+        );
+
+        // Switching dialogs doesn't close the overlay in between
+        single.run_callbacks();
+        assert!(overlay_events.try_recv().unwrap().active);
+        assert!(overlay_events.try_recv().is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn persona() {
+        let (client, single) = init();
+        let friend_id = SteamId(76561198174976054);
+        add_friend(friend_id, "Friend", FriendState::Away);
+        set_friend_nickname(friend_id, Some("Pal"));
+        set_friend_steam_level(friend_id, 42);
+        set_friend_name_history(friend_id, &["Friend", "OldName"]);
+        set_persona_state(FriendState::Invisible);
+        set_user_restrictions(UserRestrictions::VOICE_CHAT | UserRestrictions::TRADING);
+        let friends = client.friends();
+
+        assert_eq!(friends.persona_state(), FriendState::Invisible);
+        assert_eq!(
+            friends.user_restrictions(),
+            UserRestrictions::VOICE_CHAT | UserRestrictions::TRADING
+        );
+
+        let friend = friends.get_friend(friend_id);
+        assert_eq!(friend.nickname().as_deref(), Some("Pal"));
+        assert_eq!(friend.steam_level(), 42);
+        assert_eq!(friend.relationship(), FriendRelationship::Friend);
+        assert_eq!(friend.persona_name_history(1).as_deref(), Some("OldName"));
+        assert_eq!(friend.persona_name_history(2), None);
+        assert_eq!(
+            friends.request_user_information(friend_id, false),
+            UserInformation::Cached
+        );
+
+        let stranger = friends.get_friend(SteamId(76561198000000001));
+        assert_eq!(stranger.nickname(), None);
+        assert_eq!(stranger.relationship(), FriendRelationship::None);
+        assert_eq!(
+            friends.request_user_information(stranger.id(), true),
+            UserInformation::Requested
+        );
+
+        let (tx, rx) = mpsc::channel();
+        let tx2 = tx.clone();
+        friends.set_persona_name("Renamed", move |res| tx.send(res).unwrap());
+        friends.set_persona_name("", move |res| tx2.send(res).unwrap());
+        single.run_callbacks();
+        assert_eq!(rx.try_recv().unwrap(), Ok(()));
+        assert_eq!(rx.try_recv().unwrap(), Err(SteamError::InvalidParameter));
+        assert_eq!(friends.name(), "Renamed");
+    }
+
+    #[test]
+    #[serial]
+    fn avatar_loading() {
+        let (client, single) = init();
+        let friend_id = SteamId(76561198174976054);
+        add_friend(friend_id, "Friend", FriendState::Online);
+        let avatar = SteamImage {
+            width: 2,
+            height: 1,
+            rgba: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        let friend = client.friends().get_friend(friend_id);
+        assert_eq!(friend.large_avatar(), None);
+
+        // Friends without an avatar resolve right away
+        let (tx, rx) = mpsc::channel();
+        let handle = friend.load_large_avatar(move |image| tx.send(image).unwrap());
+        assert!(!handle.is_pending());
+        assert_eq!(rx.try_recv().unwrap(), None);
+        let mut future = Box::pin(friend.load_large_avatar_async());
+        assert_eq!(poll_once(future.as_mut()), Poll::Ready(None));
+
+        set_avatar_loading(friend_id);
+        let loaded = client.subscribe::<AvatarImageLoaded>();
+        let (tx, rx) = mpsc::channel();
+        let handle = friend.load_large_avatar(move |image| tx.send(image).unwrap());
+        let mut future = Box::pin(friend.load_large_avatar_async());
+        assert!(poll_once(future.as_mut()).is_pending());
+        single.run_callbacks();
+        assert!(handle.is_pending());
+        assert!(rx.try_recv().is_err());
+
+        load_avatar(friend_id, avatar.clone());
+        single.run_callbacks();
+        let event = loaded.try_recv().unwrap();
+        assert_eq!(event.steam_id, friend_id);
+        assert_eq!((event.width, event.height), (2, 1));
+        assert_eq!(
+            client.utils().image(event.image_handle),
+            Some(avatar.clone())
+        );
+        assert!(!handle.is_pending());
+        assert_eq!(rx.try_recv().unwrap(), Some(avatar.clone()));
+        assert!(!future.handle().is_pending());
+        assert_eq!(
+            poll_once(future.as_mut()),
+            Poll::Ready(Some(avatar.clone()))
+        );
+
+        // Cached avatars are passed on immediately
+        let (tx, rx) = mpsc::channel();
+        let handle = friend.load_large_avatar(move |image| tx.send(image).unwrap());
+        assert!(!handle.is_pending());
+        assert_eq!(rx.try_recv().unwrap(), Some(avatar));
+    }
+
+    #[test]
+    #[serial]
+    #[should_panic(expected = "not simulated by the fake backend")]
+    fn unsimulated_interfaces_panic() {
+        let (client, _single) = init();
+        client.networking_sockets();
+    }
+
+    #[test]
+    #[serial]
+    fn lobbies_use_call_results() {
+        let (client, single) = init();
+        let existing = add_lobby(SteamId(76561198174976054), 4);
+        set_lobby_data(existing, "mode", "coop");
+
+        let mm = client.matchmaking();
+        let (tx, rx) = mpsc::channel();
+        mm.request_lobby_list(move |lobbies| tx.send(lobbies).unwrap());
+        assert!(rx.try_recv().is_err());
+        single.run_callbacks();
+        assert_eq!(rx.try_recv().unwrap(), Ok(vec![existing]));
+        assert_eq!(mm.lobby_data(existing, "mode"), Some("coop"));
+
+        let (tx, rx) = mpsc::channel();
+        mm.join_lobby(existing, move |lobby| tx.send(lobby).unwrap());
+        single.run_callbacks();
+        assert_eq!(rx.try_recv().unwrap(), Ok(existing));
+        assert_eq!(mm.lobby_member_count(existing), 2);
+    }
+
+    #[test]
+    #[serial]
+    fn panicking_call_result() {
+        let (client, single) = init();
+        let mm = client.matchmaking();
+        mm.request_lobby_list(|_| panic!("handler failed"));
+        let panics = single.try_run_callbacks();
+        assert_eq!(panics.len(), 1);
+        assert_eq!(panics[0].message(), Some("handler failed"));
+
+        mm.request_lobby_list(|_| panic!("handler failed again"));
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| single.run_callbacks()));
+        assert!(result.is_err());
+
+        let (tx, rx) = mpsc::channel();
+        mm.request_lobby_list(move |lobbies| tx.send(lobbies).unwrap());
+        single.run_callbacks();
+        assert!(rx.try_recv().unwrap().is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn callback_pump() {
+        let (client, single) = init();
+        let pump = single.spawn_pump(std::time::Duration::from_millis(5));
+
+        let (tx, rx) = mpsc::channel();
+        client
+            .matchmaking()
+            .create_lobby(LobbyType::Public, 4, move |lobby| tx.send(lobby).unwrap());
+        let lobby = rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(lobby_members(lobby), Some(vec![SteamId(76561198040894045)]));
+
+        let single = pump.stop();
+        single.run_callbacks();
+    }
+
+    #[test]
+    #[serial]
+    fn callback_pump_handle_dropped_during_dispatch() {
+        let (client, single) = init();
+        let pump = single.spawn_pump(std::time::Duration::from_millis(5));
+
+        let (entered_tx, entered) = mpsc::channel();
+        let (resume, resume_rx) = mpsc::channel::<()>();
+        let resume_rx = Mutex::new(resume_rx);
+        let _blocking = client.register_callback(move |_: GameOverlayActivated| {
+            entered_tx.send(()).unwrap();
+            let _ = resume_rx.lock().unwrap().recv();
+        });
+        let other = client.register_callback(|_: GameOverlayActivated| {});
+        client
+            .friends()
+            .activate_game_overlay(OverlayDialog::Friends);
+        entered
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .unwrap();
+
+        // The pump is blocked in a handler waiting for this thread
+        drop(other);
+        resume.send(()).unwrap();
+        pump.stop();
+    }
+
+    #[test]
+    #[serial]
+    fn stats_and_achievements() {
+        let (client, single) = init();
+        add_stat_i32("WINS", 2);
+        add_achievement("WIN_THE_GAME", false);
+
+        let received = client.subscribe::<UserStatsReceived>();
+        let stats = client.user_stats();
+        stats.request_current_stats();
+        single.run_callbacks();
+        assert!(received.try_recv().unwrap().result.is_ok());
+
+        stats
+            .set_stat_i32("WINS", stats.get_stat_i32("WINS").unwrap() + 1)
+            .unwrap();
+        stats.achievement("WIN_THE_GAME").set().unwrap();
+        stats.store_stats().unwrap();
+        assert_eq!(stat_i32("WINS"), Some(3));
+        assert_eq!(achievement("WIN_THE_GAME"), Some(true));
+        assert!(stats_stored());
+        assert!(stats.achievement("MISSING").get().is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn cloud_files() {
+        let (client, _single) = init();
+        add_cloud_file("save.txt", b"level 1");
+
+        let rs = client.remote_storage();
+        let mut contents = String::new();
+        rs.file("save.txt")
+            .read()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "level 1");
+
+        write!(rs.file("save.txt").write(), "level 2").unwrap();
+        assert_eq!(cloud_file("save.txt").unwrap(), b"level 2");
+        assert_eq!(rs.files().len(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn workshop_queries() {
+        let (client, single) = init();
+        let item = add_workshop_item(WorkshopItem {
+            consumer_app_id: Some(AppId(480)),
+            owner: Some(SteamId(76561198040894045)),
+            title: "My map".into(),
+            tags: vec!["map".into()],
+            ..Default::default()
+        });
+
+        let (tx, rx) = mpsc::channel();
+        client
+            .ugc()
+            .query_item(item)
+            .unwrap()
+            .fetch(move |results| {
+                let results = results.unwrap();
+                tx.send(results.get(0).map(|r| r.title)).unwrap();
+            });
+        single.run_callbacks();
+        assert_eq!(rx.try_recv().unwrap(), Some("My map".to_owned()));
+
+        let (tx, rx) = mpsc::channel();
+        client
+            .ugc()
+            .subscribe_item(item, move |res| tx.send(res).unwrap());
+        single.run_callbacks();
+        assert!(rx.try_recv().unwrap().is_ok());
+        assert_eq!(client.ugc().subscribed_items(), vec![item]);
+    }
+}
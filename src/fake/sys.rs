@@ -0,0 +1,2870 @@
+//! The flat steamworks functions replaced by the fake steam client.
+//!
+//! Only the types and constants of `steamworks_sys` are re-exported,
+//! so calling a function that isn't defined here fails to compile
+//! instead of passing the fake's dangling interface pointers to the
+//! real library. Functions of the parts the fake doesn't simulate
+//! panic when called.
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+#![allow(clippy::too_many_arguments)]
+
+pub use steamworks_sys::{
+    int32, int64, kNumUGCResultsPerPage, k_HSteamListenSocket_Invalid,
+    k_HSteamNetConnection_Invalid, k_HSteamNetPollGroup_Invalid, k_cchDeveloperMetadataMax,
+    k_nSteamNetworkingSend_AutoRestartBrokenSession, k_nSteamNetworkingSend_NoDelay,
+    k_nSteamNetworkingSend_NoNagle, k_nSteamNetworkingSend_Reliable,
+    k_nSteamNetworkingSend_ReliableNoNagle, k_nSteamNetworkingSend_Unreliable,
+    k_nSteamNetworkingSend_UnreliableNoDelay, k_nSteamNetworkingSend_UnreliableNoNagle,
+    k_nSteamNetworkingSend_UseCurrentThread, uint16, uint32, uint64, uint64_steamid, uint8,
+    AccountID_t, AppId_t, AvatarImageLoaded_t, CSteamID, CSteamID_SteamID_t, CallbackMsg_t,
+    CreateItemResult_t, CreateItemResult_t_k_iCallback, DepotId_t, DlcInstalled_t,
+    DlcInstalled_t_k_iCallback, DownloadItemResult_t, DownloadItemResult_t_k_iCallback,
+    EActivateGameOverlayToWebPageMode, EAuthSessionResponse, EBeginAuthSessionResult,
+    EChatMemberStateChange, EChatRoomEnterResponse, EFloatingGamepadTextInputMode,
+    EFriendRelationship, EGamepadTextInputLineMode, EGamepadTextInputMode, EItemState,
+    EItemStatistic, EItemUpdateStatus, ELeaderboardDataRequest, ELeaderboardDisplayType,
+    ELeaderboardSortMethod, ELeaderboardUploadScoreMethod, ELobbyType, ENotificationPosition,
+    EOverlayToStoreFlag, EP2PSend, EPersonaState, ERemoteStoragePublishedFileVisibility, EResult,
+    EServerMode, ESteamNetConnectionEnd, ESteamNetworkingAvailability,
+    ESteamNetworkingConfigDataType, ESteamNetworkingConfigValue, ESteamNetworkingConnectionState,
+    ESteamNetworkingIdentityType, ESteamNetworkingSocketsDebugOutputType, ETextFilteringContext,
+    EUGCMatchingUGCType, EUserRestriction, EUserUGCList, EUserUGCListSortOrder, EWorkshopFileType,
+    FSteamNetworkingSocketsDebugOutput, FileDetailsResult_t, FileDetailsResult_t_k_iCallback,
+    FloatingGamepadTextInputDismissed_t, FloatingGamepadTextInputDismissed_t_k_iCallback,
+    FriendGameInfo_t, FriendRichPresenceUpdate_t, FriendRichPresenceUpdate_t_k_iCallback,
+    GameLobbyJoinRequested_t, GameOverlayActivated_t, GameRichPresenceJoinRequested_t,
+    GamepadTextInputDismissed_t, GetAuthSessionTicketResponse_t,
+    GetAuthSessionTicketResponse_t_k_iCallback, HAuthTicket, HSteamListenSocket,
+    HSteamNetConnection, HSteamNetPollGroup, HSteamPipe, ISteamApps, ISteamFriends,
+    ISteamGameServer, ISteamInput, ISteamMatchmaking, ISteamNetworking, ISteamNetworkingMessages,
+    ISteamNetworkingSockets, ISteamNetworkingUtils, ISteamRemoteStorage, ISteamUGC, ISteamUser,
+    ISteamUserStats, ISteamUtils, InputActionSetHandle_t, InputAnalogActionData_t,
+    InputAnalogActionHandle_t, InputDigitalActionData_t, InputDigitalActionHandle_t, InputHandle_t,
+    LeaderboardEntry_t, LeaderboardFindResult_t, LeaderboardFindResult_t_k_iCallback,
+    LeaderboardScoreUploaded_t, LeaderboardScoreUploaded_t_k_iCallback,
+    LeaderboardScoresDownloaded_t, LeaderboardScoresDownloaded_t_k_iCallback, LobbyChatUpdate_t,
+    LobbyCreated_t, LobbyCreated_t_k_iCallback, LobbyEnter_t, LobbyEnter_t_k_iCallback,
+    LobbyMatchList_t, LobbyMatchList_t_k_iCallback, LowBatteryPower_t, NewUrlLaunchParameters_t,
+    P2PSessionConnectFail_t, P2PSessionRequest_t, PersonaStateChange_t, PublishedFileId_t,
+    RemoteStorageDeletePublishedFileResult_t, RemoteStorageDeletePublishedFileResult_t_k_iCallback,
+    RemoteStorageFileReadAsyncComplete_t, RemoteStorageFileReadAsyncComplete_t_k_iCallback,
+    RemoteStorageSubscribePublishedFileResult_t,
+    RemoteStorageSubscribePublishedFileResult_t_k_iCallback,
+    RemoteStorageUnsubscribePublishedFileResult_t,
+    RemoteStorageUnsubscribePublishedFileResult_t_k_iCallback, SetPersonaNameResponse_t,
+    SetPersonaNameResponse_t_k_iCallback, SteamAPICallCompleted_t,
+    SteamAPICallCompleted_t_k_iCallback, SteamAPICall_t, SteamAPIWarningMessageHook_t,
+    SteamLeaderboardEntries_t, SteamLeaderboard_t, SteamNetConnectionInfo_t,
+    SteamNetConnectionStatusChangedCallback_t,
+    SteamNetConnectionStatusChangedCallback_t_k_iCallback, SteamNetworkingConfigValue_t,
+    SteamNetworkingConfigValue_t__bindgen_ty_1, SteamNetworkingIPAddr,
+    SteamNetworkingIPAddr_IPv4MappedAddress, SteamNetworkingIPAddr__bindgen_ty_2,
+    SteamNetworkingIPAddr_k_cchMaxString, SteamNetworkingIdentity,
+    SteamNetworkingIdentity__bindgen_ty_1, SteamNetworkingIdentity__bindgen_ty_2,
+    SteamNetworkingMessage_t, SteamNetworkingMessagesSessionFailed_t,
+    SteamNetworkingMessagesSessionFailed_t_k_iCallback, SteamNetworkingMessagesSessionRequest_t,
+    SteamNetworkingMessagesSessionRequest_t_k_iCallback, SteamParamStringArray_t,
+    SteamRelayNetworkStatus_t, SteamRelayNetworkStatus_t_k_iCallback, SteamServerConnectFailure_t,
+    SteamServersConnected_t, SteamServersDisconnected_t, SteamShutdown_t,
+    SteamShutdown_t_k_iCallback, SteamUGCDetails_t, SteamUGCQueryCompleted_t,
+    SteamUGCQueryCompleted_t_k_iCallback, SubmitItemUpdateResult_t,
+    SubmitItemUpdateResult_t_k_iCallback, UGCFileWriteStreamHandle_t, UGCQueryHandle_t,
+    UGCUpdateHandle_t, UserAchievementStored_t, UserStatsReceived_t,
+    UserStatsReceived_t_k_iCallback, UserStatsStored_t, UserStatsStored_t_k_iCallback,
+    ValidateAuthTicketResponse_t, ValidateAuthTicketResponse_t_k_iCallback, STEAM_INPUT_MAX_COUNT,
+};
+// These only work on the values passed to them and don't need a client
+pub use steamworks_sys::{
+    SteamAPI_SteamNetworkingConfigValue_t_SetFloat, SteamAPI_SteamNetworkingConfigValue_t_SetInt32,
+    SteamAPI_SteamNetworkingConfigValue_t_SetInt64,
+    SteamAPI_SteamNetworkingConfigValue_t_SetString, SteamAPI_SteamNetworkingIPAddr_Clear,
+    SteamAPI_SteamNetworkingIPAddr_GetIPv4, SteamAPI_SteamNetworkingIPAddr_IsEqualTo,
+    SteamAPI_SteamNetworkingIPAddr_IsIPv4, SteamAPI_SteamNetworkingIPAddr_SetIPv4,
+    SteamAPI_SteamNetworkingIPAddr_SetIPv6, SteamAPI_SteamNetworkingIPAddr_ToString,
+    SteamAPI_SteamNetworkingIdentity_Clear, SteamAPI_SteamNetworkingIdentity_GetIPAddr,
+    SteamAPI_SteamNetworkingIdentity_GetSteamID64, SteamAPI_SteamNetworkingIdentity_IsInvalid,
+    SteamAPI_SteamNetworkingIdentity_IsLocalHost, SteamAPI_SteamNetworkingIdentity_SetIPAddr,
+    SteamAPI_SteamNetworkingIdentity_SetLocalHost, SteamAPI_SteamNetworkingIdentity_SetSteamID64,
+    SteamAPI_SteamNetworkingIdentity_ToString, SteamAPI_SteamNetworkingMessage_t_Release,
+};
+
+use super::{
+    now, rich_presence_update, state, CloudFile, ItemUpdate, Leaderboard, LeaderboardEntry, Lobby,
+    Query, State, WorkshopItem,
+};
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::{self, NonNull};
+
+const EMPTY: &CStr = c"";
+const ENGLISH: &CStr = c"english";
+const UNKNOWN: &CStr = c"[unknown]";
+const ITEMS_PER_PAGE: usize = 50;
+
+fn interface<T>() -> *mut T {
+    NonNull::dangling().as_ptr()
+}
+
+fn steam_id(id: u64) -> CSteamID {
+    CSteamID {
+        m_steamid: CSteamID_SteamID_t { m_unAll64Bits: id },
+    }
+}
+
+unsafe fn string(s: *const c_char) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(s).to_string_lossy().into_owned()
+}
+
+/// Copies `s` into a C buffer, truncating it if needed.
+unsafe fn copy_string(dest: *mut c_char, len: usize, s: &str) {
+    if dest.is_null() || len == 0 {
+        return;
+    }
+    let count = s.len().min(len - 1);
+    ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, dest, count);
+    *dest.add(count) = 0;
+}
+
+fn take_call_result(
+    api_call: SteamAPICall_t,
+    dest: *mut c_void,
+    len: c_int,
+    expected: c_int,
+    failed: *mut bool,
+) -> bool {
+    let raw = match state().call_results.remove(&api_call) {
+        Some(raw) if raw.id == expected => raw,
+        _ => return false,
+    };
+    unsafe {
+        ptr::copy_nonoverlapping(
+            raw.data.as_ptr() as *const u8,
+            dest as *mut u8,
+            raw.size.min(len.max(0) as usize),
+        );
+        *failed = false;
+    }
+    true
+}
+
+// Core
+
+pub unsafe fn SteamAPI_Init() -> bool {
+    true
+}
+
+pub unsafe fn SteamAPI_IsSteamRunning() -> bool {
+    true
+}
+
+pub unsafe fn SteamAPI_Shutdown() {
+    let mut state = state();
+    state.queue.clear();
+    state.current = None;
+    state.call_results.clear();
+}
+
+pub unsafe fn SteamAPI_RestartAppIfNecessary(_app_id: uint32) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_GetHSteamPipe() -> HSteamPipe {
+    1
+}
+
+pub unsafe fn SteamAPI_ManualDispatch_Init() {}
+
+pub unsafe fn SteamAPI_ManualDispatch_RunFrame(_pipe: HSteamPipe) {}
+
+pub unsafe fn SteamAPI_ManualDispatch_GetNextCallback(
+    _pipe: HSteamPipe,
+    msg: *mut CallbackMsg_t,
+) -> bool {
+    let mut state = state();
+    match state.queue.pop_front() {
+        Some(mut raw) => {
+            *msg = CallbackMsg_t {
+                m_hSteamUser: 1,
+                m_iCallback: raw.id,
+                m_pubParam: raw.data.as_mut_ptr() as *mut uint8,
+                m_cubParam: raw.size as c_int,
+            };
+            state.current = Some(raw);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ManualDispatch_FreeLastCallback(_pipe: HSteamPipe) {
+    state().current = None;
+}
+
+pub unsafe fn SteamAPI_ManualDispatch_GetAPICallResult(
+    _pipe: HSteamPipe,
+    api_call: SteamAPICall_t,
+    callback: *mut c_void,
+    callback_len: c_int,
+    expected: c_int,
+    failed: *mut bool,
+) -> bool {
+    take_call_result(api_call, callback, callback_len, expected, failed)
+}
+
+pub unsafe fn SteamAPI_SteamApps_v008() -> *mut ISteamApps {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamFriends_v017() -> *mut ISteamFriends {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamMatchmaking_v009() -> *mut ISteamMatchmaking {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamNetworkingMessages_SteamAPI_v002() -> *mut ISteamNetworkingMessages {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamRemoteStorage_v016() -> *mut ISteamRemoteStorage {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamUGC_v016() -> *mut ISteamUGC {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamUserStats_v012() -> *mut ISteamUserStats {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamUser_v021() -> *mut ISteamUser {
+    interface()
+}
+
+pub unsafe fn SteamAPI_SteamUtils_v010() -> *mut ISteamUtils {
+    interface()
+}
+
+// Apps
+
+pub unsafe fn SteamAPI_ISteamApps_BIsAppInstalled(_: *mut ISteamApps, app_id: AppId_t) -> bool {
+    state().app_id == app_id
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsCybercafe(_: *mut ISteamApps) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsDlcInstalled(_: *mut ISteamApps, app_id: AppId_t) -> bool {
+    state()
+        .dlcs
+        .iter()
+        .any(|d| d.app_id == app_id && d.installed)
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetDLCCount(_: *mut ISteamApps) -> c_int {
+    state().dlcs.len() as c_int
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BGetDLCDataByIndex(
+    _: *mut ISteamApps,
+    index: c_int,
+    app_id: *mut AppId_t,
+    available: *mut bool,
+    name: *mut c_char,
+    name_len: c_int,
+) -> bool {
+    let state = state();
+    let dlc = match usize::try_from(index).ok().and_then(|i| state.dlcs.get(i)) {
+        Some(dlc) => dlc,
+        None => return false,
+    };
+    *app_id = dlc.app_id;
+    *available = true;
+    copy_string(name, name_len as usize, &dlc.name);
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamApps_InstallDLC(_: *mut ISteamApps, app_id: AppId_t) {
+    let mut state = state();
+    if let Some(dlc) = state.dlcs.iter_mut().find(|d| d.app_id == app_id) {
+        dlc.installed = true;
+        state.post(
+            DlcInstalled_t_k_iCallback as i32,
+            &DlcInstalled_t { m_nAppID: app_id },
+        );
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamApps_UninstallDLC(_: *mut ISteamApps, app_id: AppId_t) {
+    if let Some(dlc) = state().dlcs.iter_mut().find(|d| d.app_id == app_id) {
+        dlc.installed = false;
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(
+    _: *mut ISteamApps,
+    app_id: AppId_t,
+) -> uint32 {
+    let state = state();
+    if state.app_id == app_id {
+        state.purchase_time
+    } else {
+        0
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsSubscribedFromFamilySharing(_: *mut ISteamApps) -> bool {
+    state().family_shared
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsTimedTrial(
+    _: *mut ISteamApps,
+    seconds_allowed: *mut uint32,
+    seconds_played: *mut uint32,
+) -> bool {
+    match state().timed_trial {
+        Some((allowed, played)) => {
+            *seconds_allowed = allowed;
+            *seconds_played = played;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetInstalledDepots(
+    _: *mut ISteamApps,
+    app_id: AppId_t,
+    depots: *mut DepotId_t,
+    max_depots: uint32,
+) -> uint32 {
+    let state = state();
+    if state.app_id != app_id {
+        return 0;
+    }
+    let count = state.installed_depots.len().min(max_depots as usize);
+    ptr::copy_nonoverlapping(state.installed_depots.as_ptr(), depots, count);
+    count as uint32
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetFileDetails(
+    _: *mut ISteamApps,
+    file_name: *const c_char,
+) -> SteamAPICall_t {
+    let file_name = string(file_name);
+    let mut state = state();
+    let result = match state.file_details.get(&file_name) {
+        Some(details) => FileDetailsResult_t {
+            m_eResult: EResult::k_EResultOK,
+            m_ulFileSize: details.size,
+            m_FileSHA: details.sha1,
+            m_unFlags: details.flags,
+        },
+        None => FileDetailsResult_t {
+            m_eResult: EResult::k_EResultFileNotFound,
+            m_ulFileSize: 0,
+            m_FileSHA: [0; 20],
+            m_unFlags: 0,
+        },
+    };
+    state.complete(FileDetailsResult_t_k_iCallback as i32, &result)
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetLaunchQueryParam(
+    _: *mut ISteamApps,
+    key: *const c_char,
+) -> *const c_char {
+    let key = string(key);
+    match state().launch_query_params.get(&key) {
+        Some(value) => value.as_ptr(),
+        None => EMPTY.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetLaunchCommandLine(
+    _: *mut ISteamApps,
+    command_line: *mut c_char,
+    command_line_len: c_int,
+) -> c_int {
+    let state = state();
+    copy_string(
+        command_line,
+        command_line_len as usize,
+        &state.launch_command_line,
+    );
+    state
+        .launch_command_line
+        .len()
+        .min((command_line_len as usize).saturating_sub(1)) as c_int
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetDlcDownloadProgress(
+    _: *mut ISteamApps,
+    _app_id: AppId_t,
+    _downloaded: *mut uint64,
+    _total: *mut uint64,
+) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsLowViolence(_: *mut ISteamApps) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsSubscribed(_: *mut ISteamApps) -> bool {
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsSubscribedApp(_: *mut ISteamApps, app_id: AppId_t) -> bool {
+    state().app_id == app_id
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsSubscribedFromFreeWeekend(_: *mut ISteamApps) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamApps_BIsVACBanned(_: *mut ISteamApps) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetAppBuildId(_: *mut ISteamApps) -> c_int {
+    0
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetAppInstallDir(
+    _: *mut ISteamApps,
+    _app_id: AppId_t,
+    folder: *mut c_char,
+    folder_len: uint32,
+) -> uint32 {
+    copy_string(folder, folder_len as usize, "");
+    0
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetAppOwner(_: *mut ISteamApps) -> uint64_steamid {
+    state().user
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetAvailableGameLanguages(_: *mut ISteamApps) -> *const c_char {
+    ENGLISH.as_ptr()
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetCurrentBetaName(
+    _: *mut ISteamApps,
+    _name: *mut c_char,
+    _name_len: c_int,
+) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamApps_GetCurrentGameLanguage(_: *mut ISteamApps) -> *const c_char {
+    ENGLISH.as_ptr()
+}
+
+// Friends
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlay(
+    _: *mut ISteamFriends,
+    dialog: *const c_char,
+) {
+    let dialog = string(dialog);
+    state().open_overlay(dialog);
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlayInviteDialog(
+    _: *mut ISteamFriends,
+    lobby: uint64_steamid,
+) {
+    state().open_overlay(format!("LobbyInvite:{}", lobby));
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlayInviteDialogConnectString(
+    _: *mut ISteamFriends,
+    connect: *const c_char,
+) {
+    let connect = string(connect);
+    state().open_overlay(format!("Invite:{}", connect));
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlayToUser(
+    _: *mut ISteamFriends,
+    dialog: *const c_char,
+    user: uint64_steamid,
+) {
+    let dialog = string(dialog);
+    state().open_overlay(format!("{}:{}", dialog, user));
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlayToStore(
+    _: *mut ISteamFriends,
+    app_id: AppId_t,
+    _flag: EOverlayToStoreFlag,
+) {
+    state().open_overlay(format!("Store:{}", app_id));
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlayRemotePlayTogetherInviteDialog(
+    _: *mut ISteamFriends,
+    lobby: uint64_steamid,
+) {
+    state().open_overlay(format!("RemotePlayTogetherInvite:{}", lobby));
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ActivateGameOverlayToWebPage(
+    _: *mut ISteamFriends,
+    url: *const c_char,
+    _mode: EActivateGameOverlayToWebPageMode,
+) {
+    let url = string(url);
+    state().open_overlay(url);
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_InviteUserToGame(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+    connect: *const c_char,
+) -> bool {
+    let connect = string(connect);
+    let mut state = state();
+    if !state.friends.iter().any(|f| f.id == friend) {
+        return false;
+    }
+    state.invites.push((friend, connect));
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendCount(_: *mut ISteamFriends, flags: c_int) -> c_int {
+    // Every simulated friend is an immediate friend
+    if flags & 0x04 != 0 {
+        state().friends.len() as c_int
+    } else {
+        0
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendByIndex(
+    _: *mut ISteamFriends,
+    index: c_int,
+    _flags: c_int,
+) -> uint64_steamid {
+    state()
+        .friends
+        .get(index as usize)
+        .map(|f| f.id)
+        .unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendGamePlayed(
+    _: *mut ISteamFriends,
+    _friend: uint64_steamid,
+    _info: *mut FriendGameInfo_t,
+) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendPersonaName(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> *const c_char {
+    let state = state();
+    if friend == state.user {
+        return state.user_name.as_ptr();
+    }
+    match state.friends.iter().find(|f| f.id == friend) {
+        Some(f) => f.name.as_ptr(),
+        None => UNKNOWN.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendPersonaState(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> EPersonaState {
+    let state = state();
+    if friend == state.user {
+        return state.persona_state;
+    }
+    state
+        .friends
+        .iter()
+        .find(|f| f.id == friend)
+        .map(|f| f.state)
+        .unwrap_or(EPersonaState::k_EPersonaStateOffline)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetSmallFriendAvatar(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> c_int {
+    state().avatars.get(&friend).copied().unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetMediumFriendAvatar(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> c_int {
+    state().avatars.get(&friend).copied().unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetLargeFriendAvatar(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> c_int {
+    state().avatars.get(&friend).copied().unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetPersonaName(_: *mut ISteamFriends) -> *const c_char {
+    state().user_name.as_ptr()
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetPersonaState(_: *mut ISteamFriends) -> EPersonaState {
+    state().persona_state
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_SetPersonaName(
+    _: *mut ISteamFriends,
+    name: *const c_char,
+) -> SteamAPICall_t {
+    let name = CStr::from_ptr(name);
+    let mut state = state();
+    let success = !name.to_bytes().is_empty();
+    if success {
+        state.user_name = name.to_owned();
+    }
+    let result = SetPersonaNameResponse_t {
+        m_bSuccess: success,
+        m_bLocalSuccess: success,
+        m_result: if success {
+            EResult::k_EResultOK
+        } else {
+            EResult::k_EResultInvalidParam
+        },
+    };
+    state.complete(SetPersonaNameResponse_t_k_iCallback as i32, &result)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetUserRestrictions(_: *mut ISteamFriends) -> uint32 {
+    state().user_restrictions
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetPlayerNickname(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> *const c_char {
+    let state = state();
+    let nickname = state
+        .friends
+        .iter()
+        .find(|f| f.id == friend)
+        .and_then(|f| f.nickname.as_ref());
+    match nickname {
+        Some(nickname) => nickname.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendSteamLevel(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> c_int {
+    let state = state();
+    if friend == state.user {
+        return state.steam_level;
+    }
+    state
+        .friends
+        .iter()
+        .find(|f| f.id == friend)
+        .map_or(0, |f| f.steam_level)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendRelationship(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> EFriendRelationship {
+    if state().friends.iter().any(|f| f.id == friend) {
+        EFriendRelationship::k_EFriendRelationshipFriend
+    } else {
+        EFriendRelationship::k_EFriendRelationshipNone
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendPersonaNameHistory(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+    index: c_int,
+) -> *const c_char {
+    let state = state();
+    let name = state
+        .friends
+        .iter()
+        .find(|f| f.id == friend)
+        .and_then(|f| f.name_history.get(index as usize));
+    match name {
+        Some(name) => name.as_ptr(),
+        None => EMPTY.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_RequestUserInformation(
+    _: *mut ISteamFriends,
+    user: uint64_steamid,
+    _name_only: bool,
+) -> bool {
+    // Returns whether the information still has to be fetched
+    let state = state();
+    user != state.user && !state.friends.iter().any(|f| f.id == user)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_SetRichPresence(
+    _: *mut ISteamFriends,
+    key: *const c_char,
+    value: *const c_char,
+) -> bool {
+    let key = string(key);
+    let value = string(value);
+    let mut state = state();
+    if value.is_empty() {
+        state.rich_presence.remove(&key);
+    } else {
+        state.rich_presence.insert(key, value);
+    }
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_ClearRichPresence(_: *mut ISteamFriends) {
+    state().rich_presence.clear();
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendRichPresence(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+    key: *const c_char,
+) -> *const c_char {
+    let key = CStr::from_ptr(key);
+    let state = state();
+    match friend_rich_presence(&state, friend).and_then(|rp| rp.get(key)) {
+        Some(value) => value.as_ptr(),
+        None => EMPTY.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendRichPresenceKeyCount(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) -> c_int {
+    let state = state();
+    friend_rich_presence(&state, friend).map_or(0, |rp| rp.len() as c_int)
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_GetFriendRichPresenceKeyByIndex(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+    index: c_int,
+) -> *const c_char {
+    let state = state();
+    match friend_rich_presence(&state, friend).and_then(|rp| rp.keys().nth(index as usize)) {
+        Some(key) => key.as_ptr(),
+        None => EMPTY.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamFriends_RequestFriendRichPresence(
+    _: *mut ISteamFriends,
+    friend: uint64_steamid,
+) {
+    let mut state = state();
+    if state.friends.iter().any(|f| f.id == friend) {
+        let update = rich_presence_update(friend, state.app_id);
+        state.post(FriendRichPresenceUpdate_t_k_iCallback as i32, &update);
+    }
+}
+
+fn friend_rich_presence(
+    state: &State,
+    friend: uint64_steamid,
+) -> Option<&BTreeMap<CString, CString>> {
+    state
+        .friends
+        .iter()
+        .find(|f| f.id == friend)
+        .map(|f| &f.rich_presence)
+}
+
+// Matchmaking
+
+fn listed_lobbies(state: &State) -> impl Iterator<Item = &Lobby> {
+    state.lobbies.iter().filter(|l| l.joinable)
+}
+
+fn lobby(state: &State, id: u64) -> Option<&Lobby> {
+    state.lobbies.iter().find(|l| l.id == id)
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_CreateLobby(
+    _: *mut ISteamMatchmaking,
+    _lobby_type: ELobbyType,
+    max_members: c_int,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let id = 0x0186_0000_0000_0000 | state.next_handle();
+    let user = state.user;
+    state.lobbies.push(Lobby {
+        id,
+        owner: user,
+        members: vec![user],
+        member_limit: max_members,
+        joinable: true,
+        data: Default::default(),
+    });
+    state.post(
+        LobbyEnter_t_k_iCallback as i32,
+        &LobbyEnter_t {
+            m_ulSteamIDLobby: id,
+            m_rgfChatPermissions: 0,
+            m_bLocked: false,
+            m_EChatRoomEnterResponse: EChatRoomEnterResponse::k_EChatRoomEnterResponseSuccess
+                as u32,
+        },
+    );
+    state.complete(
+        LobbyCreated_t_k_iCallback as i32,
+        &LobbyCreated_t {
+            m_eResult: EResult::k_EResultOK,
+            m_ulSteamIDLobby: id,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_RequestLobbyList(
+    _: *mut ISteamMatchmaking,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let count = listed_lobbies(&state).count();
+    state.complete(
+        LobbyMatchList_t_k_iCallback as i32,
+        &LobbyMatchList_t {
+            m_nLobbiesMatching: count as u32,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_GetLobbyByIndex(
+    _: *mut ISteamMatchmaking,
+    index: c_int,
+) -> uint64_steamid {
+    let state = state();
+    let id = listed_lobbies(&state)
+        .nth(index as usize)
+        .map(|l| l.id)
+        .unwrap_or(0);
+    id
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_GetLobbyData(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+    key: *const c_char,
+) -> *const c_char {
+    let key = string(key);
+    let state = state();
+    match lobby(&state, lobby_id).and_then(|l| l.data.get(&key)) {
+        Some(value) => value.as_ptr(),
+        None => EMPTY.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_GetLobbyMemberByIndex(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+    index: c_int,
+) -> uint64_steamid {
+    let state = state();
+    let id = lobby(&state, lobby_id)
+        .and_then(|l| l.members.get(index as usize).copied())
+        .unwrap_or(0);
+    id
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_GetLobbyMemberLimit(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+) -> c_int {
+    let state = state();
+    let limit = lobby(&state, lobby_id).map(|l| l.member_limit).unwrap_or(0);
+    limit
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_GetLobbyOwner(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+) -> uint64_steamid {
+    let state = state();
+    let owner = lobby(&state, lobby_id).map(|l| l.owner).unwrap_or(0);
+    owner
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_GetNumLobbyMembers(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+) -> c_int {
+    let state = state();
+    let count = lobby(&state, lobby_id)
+        .map(|l| l.members.len() as c_int)
+        .unwrap_or(0);
+    count
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_JoinLobby(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+) -> SteamAPICall_t {
+    use EChatRoomEnterResponse::*;
+    let mut state = state();
+    let user = state.user;
+    let response = match state.lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        None => k_EChatRoomEnterResponseDoesntExist,
+        Some(lobby) if lobby.members.contains(&user) => k_EChatRoomEnterResponseSuccess,
+        Some(lobby) if !lobby.joinable => k_EChatRoomEnterResponseNotAllowed,
+        Some(lobby) if lobby.members.len() as c_int >= lobby.member_limit => {
+            k_EChatRoomEnterResponseFull
+        }
+        Some(lobby) => {
+            lobby.members.push(user);
+            k_EChatRoomEnterResponseSuccess
+        }
+    };
+    state.complete(
+        LobbyEnter_t_k_iCallback as i32,
+        &LobbyEnter_t {
+            m_ulSteamIDLobby: lobby_id,
+            m_rgfChatPermissions: 0,
+            m_bLocked: false,
+            m_EChatRoomEnterResponse: response as u32,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_LeaveLobby(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+) {
+    let mut state = state();
+    let user = state.user;
+    if let Some(lobby) = state.lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        lobby.members.retain(|&m| m != user);
+        if lobby.owner == user {
+            lobby.owner = lobby.members.first().copied().unwrap_or(0);
+        }
+    }
+    state.lobbies.retain(|l| !l.members.is_empty());
+}
+
+pub unsafe fn SteamAPI_ISteamMatchmaking_SetLobbyJoinable(
+    _: *mut ISteamMatchmaking,
+    lobby_id: uint64_steamid,
+    joinable: bool,
+) -> bool {
+    let mut state = state();
+    let user = state.user;
+    match state.lobbies.iter_mut().find(|l| l.id == lobby_id) {
+        Some(lobby) if lobby.owner == user => {
+            lobby.joinable = joinable;
+            true
+        }
+        _ => false,
+    }
+}
+
+// Networking messages
+//
+// There are no peers, nothing is received and sends fail.
+
+pub unsafe fn SteamAPI_ISteamNetworkingMessages_SendMessageToUser(
+    _: *mut ISteamNetworkingMessages,
+    _identity: *const SteamNetworkingIdentity,
+    _data: *const c_void,
+    _len: uint32,
+    _send_flags: c_int,
+    _channel: c_int,
+) -> EResult {
+    EResult::k_EResultNoConnection
+}
+
+pub unsafe fn SteamAPI_ISteamNetworkingMessages_ReceiveMessagesOnChannel(
+    _: *mut ISteamNetworkingMessages,
+    _channel: c_int,
+    _messages: *mut *mut SteamNetworkingMessage_t,
+    _max_messages: c_int,
+) -> c_int {
+    0
+}
+
+// Remote storage
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileDelete(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> bool {
+    state().files.remove(CStr::from_ptr(file)).is_some()
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileExists(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> bool {
+    state().files.contains_key(CStr::from_ptr(file))
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileForget(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> bool {
+    state().files.contains_key(CStr::from_ptr(file))
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FilePersisted(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> bool {
+    state().files.contains_key(CStr::from_ptr(file))
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileReadAsync(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+    offset: uint32,
+    len: uint32,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let api_call = state.next_handle();
+    let (offset, len) = (offset as usize, len as usize);
+    let result = match state.files.get(CStr::from_ptr(file)) {
+        Some(f) if offset + len <= f.data.len() => {
+            let data = f.data[offset..offset + len].to_vec();
+            state.reads.insert(api_call, data);
+            EResult::k_EResultOK
+        }
+        Some(_) => EResult::k_EResultInvalidParam,
+        None => EResult::k_EResultFileNotFound,
+    };
+    state.complete_call(
+        api_call,
+        RemoteStorageFileReadAsyncComplete_t_k_iCallback as i32,
+        &RemoteStorageFileReadAsyncComplete_t {
+            m_hFileReadAsync: api_call,
+            m_eResult: result,
+            m_nOffset: offset as u32,
+            m_cubRead: len as u32,
+        },
+    );
+    api_call
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileReadAsyncComplete(
+    _: *mut ISteamRemoteStorage,
+    read_call: SteamAPICall_t,
+    buffer: *mut c_void,
+    len: uint32,
+) -> bool {
+    match state().reads.remove(&read_call) {
+        Some(data) => {
+            let count = data.len().min(len as usize);
+            ptr::copy_nonoverlapping(data.as_ptr(), buffer as *mut u8, count);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileWriteStreamOpen(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> UGCFileWriteStreamHandle_t {
+    let mut state = state();
+    let handle = state.next_handle();
+    state
+        .write_streams
+        .insert(handle, (CStr::from_ptr(file).to_owned(), Vec::new()));
+    handle
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileWriteStreamWriteChunk(
+    _: *mut ISteamRemoteStorage,
+    handle: UGCFileWriteStreamHandle_t,
+    data: *const c_void,
+    len: int32,
+) -> bool {
+    match state().write_streams.get_mut(&handle) {
+        Some((_, buffer)) if len >= 0 => {
+            buffer.extend_from_slice(std::slice::from_raw_parts(data as *const u8, len as usize));
+            true
+        }
+        _ => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_FileWriteStreamClose(
+    _: *mut ISteamRemoteStorage,
+    handle: UGCFileWriteStreamHandle_t,
+) -> bool {
+    let mut state = state();
+    match state.write_streams.remove(&handle) {
+        Some((name, data)) => {
+            state.files.insert(
+                name,
+                CloudFile {
+                    data,
+                    timestamp: now() as i64,
+                },
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_GetFileCount(_: *mut ISteamRemoteStorage) -> int32 {
+    state().files.len() as int32
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_GetFileNameAndSize(
+    _: *mut ISteamRemoteStorage,
+    index: c_int,
+    size: *mut int32,
+) -> *const c_char {
+    let state = state();
+    match state.files.iter().nth(index as usize) {
+        Some((name, file)) => {
+            *size = file.data.len() as int32;
+            name.as_ptr()
+        }
+        None => {
+            *size = 0;
+            EMPTY.as_ptr()
+        }
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_GetFileSize(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> int32 {
+    state()
+        .files
+        .get(CStr::from_ptr(file))
+        .map(|f| f.data.len() as int32)
+        .unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_GetFileTimestamp(
+    _: *mut ISteamRemoteStorage,
+    file: *const c_char,
+) -> int64 {
+    state()
+        .files
+        .get(CStr::from_ptr(file))
+        .map(|f| f.timestamp)
+        .unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_IsCloudEnabledForAccount(
+    _: *mut ISteamRemoteStorage,
+) -> bool {
+    state().cloud_enabled_for_account
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_IsCloudEnabledForApp(
+    _: *mut ISteamRemoteStorage,
+) -> bool {
+    state().cloud_enabled_for_app
+}
+
+pub unsafe fn SteamAPI_ISteamRemoteStorage_SetCloudEnabledForApp(
+    _: *mut ISteamRemoteStorage,
+    enabled: bool,
+) {
+    state().cloud_enabled_for_app = enabled;
+}
+
+// UGC
+
+fn new_query(state: &mut State, items: Vec<u64>, page: u32) -> UGCQueryHandle_t {
+    let handle = state.next_handle();
+    state.queries.insert(
+        handle,
+        Query {
+            items,
+            required_tags: Vec::new(),
+            excluded_tags: Vec::new(),
+            match_any_tag: false,
+            total_only: false,
+            only_ids: false,
+            page,
+        },
+    );
+    handle
+}
+
+fn with_query(handle: UGCQueryHandle_t, f: impl FnOnce(&mut Query)) -> bool {
+    match state().queries.get_mut(&handle) {
+        Some(query) => {
+            f(query);
+            true
+        }
+        None => false,
+    }
+}
+
+fn with_update(handle: UGCUpdateHandle_t, f: impl FnOnce(&mut ItemUpdate)) -> bool {
+    match state().item_updates.get_mut(&handle) {
+        Some(update) => {
+            f(update);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the item at `index` in the current page of a sent query.
+fn query_item(state: &State, handle: UGCQueryHandle_t, index: uint32) -> Option<&WorkshopItem> {
+    let query = state.queries.get(&handle)?;
+    let id = query.items.get(index as usize)?;
+    state.workshop_items.get(id)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_CreateItem(
+    _: *mut ISteamUGC,
+    app_id: AppId_t,
+    _file_type: EWorkshopFileType,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let id = state.next_handle();
+    let time = now();
+    let owner = state.user;
+    state.workshop_items.insert(
+        id,
+        WorkshopItem {
+            published_file_id: crate::PublishedFileId(id),
+            consumer_app_id: Some(crate::AppId(app_id)),
+            owner: Some(crate::SteamId(owner)),
+            time_created: time,
+            time_updated: time,
+            ..Default::default()
+        },
+    );
+    state.complete(
+        CreateItemResult_t_k_iCallback as i32,
+        &CreateItemResult_t {
+            m_eResult: EResult::k_EResultOK,
+            m_nPublishedFileId: id,
+            m_bUserNeedsToAcceptWorkshopLegalAgreement: false,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_DeleteItem(
+    _: *mut ISteamUGC,
+    id: PublishedFileId_t,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let result = if state.workshop_items.remove(&id).is_some() {
+        state.subscribed_items.remove(&id);
+        EResult::k_EResultOK
+    } else {
+        EResult::k_EResultFileNotFound
+    };
+    state.complete(
+        RemoteStorageDeletePublishedFileResult_t_k_iCallback as i32,
+        &RemoteStorageDeletePublishedFileResult_t {
+            m_eResult: result,
+            m_nPublishedFileId: id,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SubscribeItem(
+    _: *mut ISteamUGC,
+    id: PublishedFileId_t,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let result = if state.workshop_items.contains_key(&id) {
+        state.subscribed_items.insert(id);
+        EResult::k_EResultOK
+    } else {
+        EResult::k_EResultFileNotFound
+    };
+    state.complete(
+        RemoteStorageSubscribePublishedFileResult_t_k_iCallback as i32,
+        &RemoteStorageSubscribePublishedFileResult_t {
+            m_eResult: result,
+            m_nPublishedFileId: id,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_UnsubscribeItem(
+    _: *mut ISteamUGC,
+    id: PublishedFileId_t,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let result = if state.subscribed_items.remove(&id) {
+        EResult::k_EResultOK
+    } else {
+        EResult::k_EResultFail
+    };
+    state.complete(
+        RemoteStorageUnsubscribePublishedFileResult_t_k_iCallback as i32,
+        &RemoteStorageUnsubscribePublishedFileResult_t {
+            m_eResult: result,
+            m_nPublishedFileId: id,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetNumSubscribedItems(_: *mut ISteamUGC) -> uint32 {
+    state().subscribed_items.len() as uint32
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetSubscribedItems(
+    _: *mut ISteamUGC,
+    ids: *mut PublishedFileId_t,
+    max: uint32,
+) -> uint32 {
+    let state = state();
+    let mut count = 0;
+    for &id in state.subscribed_items.iter().take(max as usize) {
+        *ids.add(count) = id;
+        count += 1;
+    }
+    count as uint32
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetItemState(_: *mut ISteamUGC, id: PublishedFileId_t) -> uint32 {
+    // Subscribed items are treated as installed
+    if state().subscribed_items.contains(&id) {
+        EItemState::k_EItemStateSubscribed as uint32 | EItemState::k_EItemStateInstalled as uint32
+    } else {
+        EItemState::k_EItemStateNone as uint32
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetItemDownloadInfo(
+    _: *mut ISteamUGC,
+    _id: PublishedFileId_t,
+    _downloaded: *mut uint64,
+    _total: *mut uint64,
+) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetItemInstallInfo(
+    _: *mut ISteamUGC,
+    _id: PublishedFileId_t,
+    _size_on_disk: *mut uint64,
+    _folder: *mut c_char,
+    _folder_len: uint32,
+    _timestamp: *mut uint32,
+) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_DownloadItem(
+    _: *mut ISteamUGC,
+    id: PublishedFileId_t,
+    _high_priority: bool,
+) -> bool {
+    let mut state = state();
+    if !state.workshop_items.contains_key(&id) {
+        return false;
+    }
+    let app_id = state.app_id;
+    state.post(
+        DownloadItemResult_t_k_iCallback as i32,
+        &DownloadItemResult_t {
+            m_unAppID: app_id,
+            m_nPublishedFileId: id,
+            m_eResult: EResult::k_EResultOK,
+        },
+    );
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SuspendDownloads(_: *mut ISteamUGC, _suspend: bool) {}
+
+pub unsafe fn SteamAPI_ISteamUGC_BInitWorkshopForGameServer(
+    _: *mut ISteamUGC,
+    _depot: DepotId_t,
+    _folder: *const c_char,
+) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_StartItemUpdate(
+    _: *mut ISteamUGC,
+    _app_id: AppId_t,
+    id: PublishedFileId_t,
+) -> UGCUpdateHandle_t {
+    let mut state = state();
+    let handle = state.next_handle();
+    state.item_updates.insert(
+        handle,
+        ItemUpdate {
+            published_file_id: id,
+            ..Default::default()
+        },
+    );
+    handle
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemTitle(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    title: *const c_char,
+) -> bool {
+    let title = string(title);
+    with_update(handle, |u| u.title = Some(title))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemDescription(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    description: *const c_char,
+) -> bool {
+    let description = string(description);
+    with_update(handle, |u| u.description = Some(description))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemMetadata(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    metadata: *const c_char,
+) -> bool {
+    let metadata = string(metadata);
+    with_update(handle, |u| u.metadata = Some(metadata))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemPreview(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    _preview_file: *const c_char,
+) -> bool {
+    with_update(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemContent(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    _content_folder: *const c_char,
+) -> bool {
+    with_update(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemVisibility(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    _visibility: ERemoteStoragePublishedFileVisibility,
+) -> bool {
+    with_update(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetItemTags(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    tags: *const SteamParamStringArray_t,
+) -> bool {
+    let tags = &*tags;
+    let tags = (0..tags.m_nNumStrings.max(0) as usize)
+        .map(|i| string(*tags.m_ppStrings.add(i)))
+        .collect();
+    with_update(handle, |u| u.tags = Some(tags))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_AddItemKeyValueTag(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    key: *const c_char,
+    value: *const c_char,
+) -> bool {
+    let (key, value) = (string(key), string(value));
+    with_update(handle, |u| u.key_value_tags.push((key, value)))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_RemoveItemKeyValueTags(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    key: *const c_char,
+) -> bool {
+    let key = string(key);
+    with_update(handle, |u| u.removed_key_value_tags.push(key))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_RemoveAllItemKeyValueTags(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+) -> bool {
+    with_update(handle, |u| u.remove_all_key_value_tags = true)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SubmitItemUpdate(
+    _: *mut ISteamUGC,
+    handle: UGCUpdateHandle_t,
+    _change_note: *const c_char,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let update = state.item_updates.remove(&handle).unwrap_or_default();
+    let id = update.published_file_id;
+    let result = match state.workshop_items.get_mut(&id) {
+        Some(item) => {
+            if let Some(title) = update.title {
+                item.title = title;
+            }
+            if let Some(description) = update.description {
+                item.description = description;
+            }
+            if let Some(metadata) = update.metadata {
+                item.metadata = metadata;
+            }
+            if let Some(tags) = update.tags {
+                item.tags = tags;
+            }
+            if update.remove_all_key_value_tags {
+                item.key_value_tags.clear();
+            }
+            item.key_value_tags
+                .retain(|(k, _)| !update.removed_key_value_tags.contains(k));
+            item.key_value_tags.extend(update.key_value_tags);
+            item.time_updated = now();
+            EResult::k_EResultOK
+        }
+        None => EResult::k_EResultFileNotFound,
+    };
+    state.complete(
+        SubmitItemUpdateResult_t_k_iCallback as i32,
+        &SubmitItemUpdateResult_t {
+            m_eResult: result,
+            m_bUserNeedsToAcceptWorkshopLegalAgreement: false,
+            m_nPublishedFileId: id,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetItemUpdateProgress(
+    _: *mut ISteamUGC,
+    _handle: UGCUpdateHandle_t,
+    processed: *mut uint64,
+    total: *mut uint64,
+) -> EItemUpdateStatus {
+    *processed = 0;
+    *total = 0;
+    EItemUpdateStatus::k_EItemUpdateStatusInvalid
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_CreateQueryUserUGCRequest(
+    _: *mut ISteamUGC,
+    account_id: AccountID_t,
+    list_type: EUserUGCList,
+    _matching_type: EUGCMatchingUGCType,
+    _sort_order: EUserUGCListSortOrder,
+    _creator_app_id: AppId_t,
+    consumer_app_id: AppId_t,
+    page: uint32,
+) -> UGCQueryHandle_t {
+    let mut state = state();
+    let items = state
+        .workshop_items
+        .values()
+        .filter(|item| {
+            item.consumer_app_id
+                .is_none_or(|app| consumer_app_id == 0 || app.0 == consumer_app_id)
+        })
+        .filter(|item| match list_type {
+            EUserUGCList::k_EUserUGCList_Published => {
+                item.owner.map(|o| o.0 as u32) == Some(account_id)
+            }
+            EUserUGCList::k_EUserUGCList_Subscribed => {
+                state.subscribed_items.contains(&item.published_file_id.0)
+            }
+            _ => false,
+        })
+        .map(|item| item.published_file_id.0)
+        .collect();
+    new_query(&mut state, items, page)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_CreateQueryUGCDetailsRequest(
+    _: *mut ISteamUGC,
+    ids: *mut PublishedFileId_t,
+    count: uint32,
+) -> UGCQueryHandle_t {
+    let items = std::slice::from_raw_parts(ids, count as usize).to_vec();
+    new_query(&mut state(), items, 0)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_AddRequiredTag(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    tag: *const c_char,
+) -> bool {
+    let tag = string(tag);
+    with_query(handle, |q| q.required_tags.push(tag))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_AddExcludedTag(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    tag: *const c_char,
+) -> bool {
+    let tag = string(tag);
+    with_query(handle, |q| q.excluded_tags.push(tag))
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetMatchAnyTag(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    any: bool,
+) -> bool {
+    with_query(handle, |q| q.match_any_tag = any)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnTotalOnly(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    total_only: bool,
+) -> bool {
+    with_query(handle, |q| q.total_only = total_only)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnOnlyIDs(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    only_ids: bool,
+) -> bool {
+    with_query(handle, |q| q.only_ids = only_ids)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetLanguage(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _language: *const c_char,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetAllowCachedResponse(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _max_age_seconds: uint32,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnLongDescription(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _value: bool,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnChildren(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _value: bool,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnMetadata(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _value: bool,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnAdditionalPreviews(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _value: bool,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SetReturnKeyValueTags(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    _value: bool,
+) -> bool {
+    with_query(handle, |_| {})
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_SendQueryUGCRequest(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let state = &mut *state;
+    let (result, returned, total) = match state.queries.get_mut(&handle) {
+        Some(query) => {
+            let items = &state.workshop_items;
+            let has_tag =
+                |id: &u64, tag: &String| items.get(id).is_some_and(|item| item.tags.contains(tag));
+            let (required, excluded, any) = (
+                &query.required_tags,
+                &query.excluded_tags,
+                query.match_any_tag,
+            );
+            query.items.retain(|id| {
+                let required = if any {
+                    required.is_empty() || required.iter().any(|t| has_tag(id, t))
+                } else {
+                    required.iter().all(|t| has_tag(id, t))
+                };
+                required && !excluded.iter().any(|t| has_tag(id, t))
+            });
+            let total = query.items.len();
+            // User queries are paged, pages start at 1
+            if query.page > 0 {
+                let start = (query.page as usize - 1) * ITEMS_PER_PAGE;
+                query.items = query
+                    .items
+                    .iter()
+                    .skip(start)
+                    .take(ITEMS_PER_PAGE)
+                    .copied()
+                    .collect();
+            }
+            if query.total_only {
+                query.items.clear();
+            }
+            (EResult::k_EResultOK, query.items.len(), total)
+        }
+        None => (EResult::k_EResultInvalidParam, 0, 0),
+    };
+    state.complete(
+        SteamUGCQueryCompleted_t_k_iCallback as i32,
+        &SteamUGCQueryCompleted_t {
+            m_handle: handle,
+            m_eResult: result,
+            m_unNumResultsReturned: returned as uint32,
+            m_unTotalMatchingResults: total as uint32,
+            m_bCachedData: false,
+            m_rgchNextCursor: [0; 256],
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+) -> bool {
+    state().queries.remove(&handle).is_some()
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCResult(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+    details: *mut SteamUGCDetails_t,
+) -> bool {
+    let state = state();
+    let query = match state.queries.get(&handle) {
+        Some(query) => query,
+        None => return false,
+    };
+    let id = match query.items.get(index as usize) {
+        Some(&id) => id,
+        None => return false,
+    };
+    let details = &mut *details;
+    details.m_nPublishedFileId = id;
+    let item = match state.workshop_items.get(&id) {
+        Some(item) => item,
+        None => {
+            details.m_eResult = EResult::k_EResultFileNotFound;
+            return true;
+        }
+    };
+    details.m_eResult = EResult::k_EResultOK;
+    if query.only_ids {
+        return true;
+    }
+    details.m_eFileType = EWorkshopFileType::k_EWorkshopFileTypeCommunity;
+    details.m_nCreatorAppID = item.consumer_app_id.map(|a| a.0).unwrap_or(0);
+    details.m_nConsumerAppID = item.consumer_app_id.map(|a| a.0).unwrap_or(0);
+    copy_string(
+        details.m_rgchTitle.as_mut_ptr(),
+        details.m_rgchTitle.len(),
+        &item.title,
+    );
+    copy_string(
+        details.m_rgchDescription.as_mut_ptr(),
+        details.m_rgchDescription.len(),
+        &item.description,
+    );
+    details.m_ulSteamIDOwner = item.owner.map(|o| o.0).unwrap_or(0);
+    details.m_rtimeCreated = item.time_created;
+    details.m_rtimeUpdated = item.time_updated;
+    details.m_eVisibility =
+        ERemoteStoragePublishedFileVisibility::k_ERemoteStoragePublishedFileVisibilityPublic;
+    details.m_bAcceptedForUse = true;
+    copy_string(
+        details.m_rgchTags.as_mut_ptr(),
+        details.m_rgchTags.len(),
+        &item.tags.join(","),
+    );
+    details.m_bTagsTruncated = false;
+    details.m_unNumChildren = item.children.len() as uint32;
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCPreviewURL(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+    url: *mut c_char,
+    url_len: uint32,
+) -> bool {
+    match query_item(&state(), handle, index) {
+        Some(item) => {
+            copy_string(url, url_len as usize, &item.preview_url);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCMetadata(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+    metadata: *mut c_char,
+    metadata_len: uint32,
+) -> bool {
+    match query_item(&state(), handle, index) {
+        Some(item) => {
+            copy_string(metadata, metadata_len as usize, &item.metadata);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCChildren(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+    ids: *mut PublishedFileId_t,
+    max: uint32,
+) -> bool {
+    match query_item(&state(), handle, index) {
+        Some(item) => {
+            for (i, child) in item.children.iter().take(max as usize).enumerate() {
+                *ids.add(i) = child.0;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCStatistic(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+    _stat_type: EItemStatistic,
+    value: *mut uint64,
+) -> bool {
+    match query_item(&state(), handle, index) {
+        Some(_) => {
+            *value = 0;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCNumKeyValueTags(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+) -> uint32 {
+    query_item(&state(), handle, index)
+        .map(|item| item.key_value_tags.len() as uint32)
+        .unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamUGC_GetQueryUGCKeyValueTag(
+    _: *mut ISteamUGC,
+    handle: UGCQueryHandle_t,
+    index: uint32,
+    tag_index: uint32,
+    key: *mut c_char,
+    key_len: uint32,
+    value: *mut c_char,
+    value_len: uint32,
+) -> bool {
+    let state = state();
+    match query_item(&state, handle, index).and_then(|i| i.key_value_tags.get(tag_index as usize)) {
+        Some((k, v)) => {
+            copy_string(key, key_len as usize, k);
+            copy_string(value, value_len as usize, v);
+            true
+        }
+        None => false,
+    }
+}
+
+// User
+
+pub unsafe fn SteamAPI_ISteamUser_GetSteamID(_: *mut ISteamUser) -> uint64_steamid {
+    state().user
+}
+
+pub unsafe fn SteamAPI_ISteamUser_GetPlayerSteamLevel(_: *mut ISteamUser) -> c_int {
+    state().steam_level
+}
+
+pub unsafe fn SteamAPI_ISteamUser_GetAuthSessionTicket(
+    _: *mut ISteamUser,
+    ticket: *mut c_void,
+    max_ticket: c_int,
+    ticket_len: *mut uint32,
+) -> HAuthTicket {
+    // The ticket is just the steam id of the user that created it
+    let mut state = state();
+    let data = state.user.to_le_bytes();
+    if (max_ticket as usize) < data.len() {
+        *ticket_len = 0;
+        return 0;
+    }
+    ptr::copy_nonoverlapping(data.as_ptr(), ticket as *mut u8, data.len());
+    *ticket_len = data.len() as uint32;
+    let handle = state.next_handle() as HAuthTicket;
+    state.post(
+        GetAuthSessionTicketResponse_t_k_iCallback as i32,
+        &GetAuthSessionTicketResponse_t {
+            m_hAuthTicket: handle,
+            m_eResult: EResult::k_EResultOK,
+        },
+    );
+    handle
+}
+
+pub unsafe fn SteamAPI_ISteamUser_CancelAuthTicket(_: *mut ISteamUser, _ticket: HAuthTicket) {}
+
+pub unsafe fn SteamAPI_ISteamUser_BeginAuthSession(
+    _: *mut ISteamUser,
+    ticket: *const c_void,
+    ticket_len: c_int,
+    user: uint64_steamid,
+) -> EBeginAuthSessionResult {
+    if ticket_len != 8 {
+        return EBeginAuthSessionResult::k_EBeginAuthSessionResultInvalidTicket;
+    }
+    let mut data = [0; 8];
+    ptr::copy_nonoverlapping(ticket as *const u8, data.as_mut_ptr(), 8);
+    if u64::from_le_bytes(data) != user {
+        return EBeginAuthSessionResult::k_EBeginAuthSessionResultInvalidTicket;
+    }
+    state().post(
+        ValidateAuthTicketResponse_t_k_iCallback as i32,
+        &ValidateAuthTicketResponse_t {
+            m_SteamID: steam_id(user),
+            m_eAuthSessionResponse: EAuthSessionResponse::k_EAuthSessionResponseOK,
+            m_OwnerSteamID: steam_id(user),
+        },
+    );
+    EBeginAuthSessionResult::k_EBeginAuthSessionResultOK
+}
+
+pub unsafe fn SteamAPI_ISteamUser_EndAuthSession(_: *mut ISteamUser, _user: uint64_steamid) {}
+
+// User stats
+
+fn leaderboard(state: &mut State, handle: SteamLeaderboard_t) -> Option<&mut Leaderboard> {
+    state
+        .leaderboards
+        .get_mut((handle as usize).checked_sub(1)?)
+}
+
+fn sort_entries(leaderboard: &mut Leaderboard) {
+    match leaderboard.sort_method {
+        ELeaderboardSortMethod::k_ELeaderboardSortMethodAscending => {
+            leaderboard.entries.sort_by_key(|e| e.score)
+        }
+        _ => leaderboard
+            .entries
+            .sort_by_key(|e| std::cmp::Reverse(e.score)),
+    }
+}
+
+fn find_leaderboard(
+    state: &mut State,
+    name: *const c_char,
+    create: Option<(ELeaderboardSortMethod, ELeaderboardDisplayType)>,
+) -> SteamAPICall_t {
+    let name = unsafe { CStr::from_ptr(name) };
+    let mut index = state
+        .leaderboards
+        .iter()
+        .position(|l| l.name.as_c_str() == name);
+    if let (None, Some((sort_method, display_type))) = (index, create) {
+        state.leaderboards.push(Leaderboard {
+            name: name.to_owned(),
+            sort_method,
+            display_type,
+            entries: Vec::new(),
+        });
+        index = Some(state.leaderboards.len() - 1);
+    }
+    state.complete(
+        LeaderboardFindResult_t_k_iCallback as i32,
+        &LeaderboardFindResult_t {
+            m_hSteamLeaderboard: index.map(|i| i as u64 + 1).unwrap_or(0),
+            m_bLeaderboardFound: index.is_some() as uint8,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_RequestCurrentStats(_: *mut ISteamUserStats) -> bool {
+    let mut state = state();
+    let (app_id, user) = (state.app_id, state.user);
+    state.post(
+        UserStatsReceived_t_k_iCallback as i32,
+        &UserStatsReceived_t {
+            m_nGameID: app_id as u64,
+            m_eResult: EResult::k_EResultOK,
+            m_steamIDUser: steam_id(user),
+        },
+    );
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_StoreStats(_: *mut ISteamUserStats) -> bool {
+    let mut state = state();
+    state.stats_stored = true;
+    let app_id = state.app_id;
+    state.post(
+        UserStatsStored_t_k_iCallback as i32,
+        &UserStatsStored_t {
+            m_nGameID: app_id as u64,
+            m_eResult: EResult::k_EResultOK,
+        },
+    );
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_ResetAllStats(
+    _: *mut ISteamUserStats,
+    achievements_too: bool,
+) -> bool {
+    let mut state = state();
+    state.stats_i32.values_mut().for_each(|v| *v = 0);
+    state.stats_f32.values_mut().for_each(|v| *v = 0.0);
+    if achievements_too {
+        state.achievements.values_mut().for_each(|v| *v = false);
+    }
+    state.stats_stored = false;
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetStatInt32(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+    data: *mut int32,
+) -> bool {
+    match state().stats_i32.get(&string(name)) {
+        Some(&value) => {
+            *data = value;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_SetStatInt32(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+    data: int32,
+) -> bool {
+    let mut state = state();
+    match state.stats_i32.get_mut(&string(name)) {
+        Some(value) => {
+            *value = data;
+            state.stats_stored = false;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetStatFloat(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+    data: *mut f32,
+) -> bool {
+    match state().stats_f32.get(&string(name)) {
+        Some(&value) => {
+            *data = value;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_SetStatFloat(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+    data: f32,
+) -> bool {
+    let mut state = state();
+    match state.stats_f32.get_mut(&string(name)) {
+        Some(value) => {
+            *value = data;
+            state.stats_stored = false;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetAchievementIcon(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+) -> c_int {
+    state()
+        .achievement_icons
+        .get(&string(name))
+        .copied()
+        .unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetAchievement(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+    achieved: *mut bool,
+) -> bool {
+    match state().achievements.get(&string(name)) {
+        Some(&value) => {
+            *achieved = value;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_SetAchievement(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+) -> bool {
+    let mut state = state();
+    match state.achievements.get_mut(&string(name)) {
+        Some(value) => {
+            *value = true;
+            state.stats_stored = false;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_ClearAchievement(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+) -> bool {
+    let mut state = state();
+    match state.achievements.get_mut(&string(name)) {
+        Some(value) => {
+            *value = false;
+            state.stats_stored = false;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_FindLeaderboard(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+) -> SteamAPICall_t {
+    find_leaderboard(&mut state(), name, None)
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_FindOrCreateLeaderboard(
+    _: *mut ISteamUserStats,
+    name: *const c_char,
+    sort_method: ELeaderboardSortMethod,
+    display_type: ELeaderboardDisplayType,
+) -> SteamAPICall_t {
+    find_leaderboard(&mut state(), name, Some((sort_method, display_type)))
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetLeaderboardName(
+    _: *mut ISteamUserStats,
+    handle: SteamLeaderboard_t,
+) -> *const c_char {
+    match leaderboard(&mut state(), handle) {
+        Some(l) => l.name.as_ptr(),
+        None => EMPTY.as_ptr(),
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetLeaderboardEntryCount(
+    _: *mut ISteamUserStats,
+    handle: SteamLeaderboard_t,
+) -> c_int {
+    leaderboard(&mut state(), handle)
+        .map(|l| l.entries.len() as c_int)
+        .unwrap_or(0)
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetLeaderboardSortMethod(
+    _: *mut ISteamUserStats,
+    handle: SteamLeaderboard_t,
+) -> ELeaderboardSortMethod {
+    leaderboard(&mut state(), handle)
+        .map(|l| l.sort_method)
+        .unwrap_or(ELeaderboardSortMethod::k_ELeaderboardSortMethodNone)
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetLeaderboardDisplayType(
+    _: *mut ISteamUserStats,
+    handle: SteamLeaderboard_t,
+) -> ELeaderboardDisplayType {
+    leaderboard(&mut state(), handle)
+        .map(|l| l.display_type)
+        .unwrap_or(ELeaderboardDisplayType::k_ELeaderboardDisplayTypeNone)
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_UploadLeaderboardScore(
+    _: *mut ISteamUserStats,
+    handle: SteamLeaderboard_t,
+    method: ELeaderboardUploadScoreMethod,
+    score: int32,
+    details: *const int32,
+    details_count: c_int,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let user = state.user;
+    let details = if details.is_null() {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(details, details_count.max(0) as usize).to_vec()
+    };
+    let mut result = LeaderboardScoreUploaded_t {
+        m_bSuccess: 0,
+        m_hSteamLeaderboard: handle,
+        m_nScore: score,
+        m_bScoreChanged: 0,
+        m_nGlobalRankNew: 0,
+        m_nGlobalRankPrevious: 0,
+    };
+    if let Some(leaderboard) = leaderboard(&mut state, handle) {
+        let rank = |l: &Leaderboard| {
+            l.entries
+                .iter()
+                .position(|e| e.user == user)
+                .map(|i| i as c_int + 1)
+                .unwrap_or(0)
+        };
+        result.m_bSuccess = 1;
+        result.m_nGlobalRankPrevious = rank(leaderboard);
+        let ascending =
+            leaderboard.sort_method == ELeaderboardSortMethod::k_ELeaderboardSortMethodAscending;
+        let entry = LeaderboardEntry {
+            user,
+            score,
+            details,
+        };
+        match leaderboard.entries.iter_mut().find(|e| e.user == user) {
+            Some(existing) => {
+                let better = if ascending {
+                    score < existing.score
+                } else {
+                    score > existing.score
+                };
+                if better
+                    || method
+                        == ELeaderboardUploadScoreMethod::k_ELeaderboardUploadScoreMethodForceUpdate
+                {
+                    result.m_bScoreChanged = (existing.score != score) as uint8;
+                    *existing = entry;
+                }
+            }
+            None => {
+                result.m_bScoreChanged = 1;
+                leaderboard.entries.push(entry);
+            }
+        }
+        sort_entries(leaderboard);
+        result.m_nGlobalRankNew = rank(leaderboard);
+    }
+    state.complete(LeaderboardScoreUploaded_t_k_iCallback as i32, &result)
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_DownloadLeaderboardEntries(
+    _: *mut ISteamUserStats,
+    handle: SteamLeaderboard_t,
+    request: ELeaderboardDataRequest,
+    start: c_int,
+    end: c_int,
+) -> SteamAPICall_t {
+    let mut state = state();
+    let user = state.user;
+    let friends: Vec<u64> = state.friends.iter().map(|f| f.id).collect();
+    let ranked: Vec<(i32, LeaderboardEntry)> = leaderboard(&mut state, handle)
+        .map(|l| {
+            l.entries
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(i, e)| (i as i32 + 1, e))
+                .collect()
+        })
+        .unwrap_or_default();
+    let entries: Vec<_> = match request {
+        ELeaderboardDataRequest::k_ELeaderboardDataRequestGlobal => ranked
+            .into_iter()
+            .filter(|(rank, _)| *rank >= start && *rank <= end)
+            .collect(),
+        ELeaderboardDataRequest::k_ELeaderboardDataRequestGlobalAroundUser => {
+            match ranked.iter().find(|(_, e)| e.user == user).map(|(r, _)| *r) {
+                Some(user_rank) => ranked
+                    .into_iter()
+                    .filter(|(rank, _)| *rank >= user_rank + start && *rank <= user_rank + end)
+                    .collect(),
+                None => Vec::new(),
+            }
+        }
+        _ => ranked
+            .into_iter()
+            .filter(|(_, e)| e.user == user || friends.contains(&e.user))
+            .collect(),
+    };
+    let entries_handle = state.next_handle();
+    let count = entries.len() as c_int;
+    state.downloaded_entries.insert(entries_handle, entries);
+    state.complete(
+        LeaderboardScoresDownloaded_t_k_iCallback as i32,
+        &LeaderboardScoresDownloaded_t {
+            m_hSteamLeaderboard: handle,
+            m_hSteamLeaderboardEntries: entries_handle,
+            m_cEntryCount: count,
+        },
+    )
+}
+
+pub unsafe fn SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
+    _: *mut ISteamUserStats,
+    entries: SteamLeaderboardEntries_t,
+    index: c_int,
+    entry: *mut LeaderboardEntry_t,
+    details: *mut int32,
+    details_max: c_int,
+) -> bool {
+    let state = state();
+    let (rank, e) = match state
+        .downloaded_entries
+        .get(&entries)
+        .and_then(|entries| entries.get(index as usize))
+    {
+        Some(e) => e,
+        None => return false,
+    };
+    *entry = LeaderboardEntry_t {
+        m_steamIDUser: steam_id(e.user),
+        m_nGlobalRank: *rank,
+        m_nScore: e.score,
+        m_cDetails: e.details.len() as int32,
+        m_hUGC: 0,
+    };
+    if !details.is_null() {
+        let count = e.details.len().min(details_max.max(0) as usize);
+        ptr::copy_nonoverlapping(e.details.as_ptr(), details, count);
+    }
+    true
+}
+
+// Utils
+
+pub unsafe fn SteamAPI_ISteamUtils_GetAppID(_: *mut ISteamUtils) -> uint32 {
+    state().app_id
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetIPCountry(_: *mut ISteamUtils) -> *const c_char {
+    c"US".as_ptr()
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetSteamUILanguage(_: *mut ISteamUtils) -> *const c_char {
+    ENGLISH.as_ptr()
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetServerRealTime(_: *mut ISteamUtils) -> uint32 {
+    now()
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_IsSteamRunningOnSteamDeck(_: *mut ISteamUtils) -> bool {
+    state().steam_deck
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_IsSteamInBigPictureMode(_: *mut ISteamUtils) -> bool {
+    state().big_picture
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_IsSteamChinaLauncher(_: *mut ISteamUtils) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_IsVRHeadsetStreamingEnabled(_: *mut ISteamUtils) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_IsOverlayEnabled(_: *mut ISteamUtils) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_BOverlayNeedsPresent(_: *mut ISteamUtils) -> bool {
+    false
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetSecondsSinceAppActive(_: *mut ISteamUtils) -> uint32 {
+    0
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetCurrentBatteryPower(_: *mut ISteamUtils) -> uint8 {
+    state().battery_power.unwrap_or(255)
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_ShowGamepadTextInput(
+    _: *mut ISteamUtils,
+    _input_mode: EGamepadTextInputMode,
+    _line_mode: EGamepadTextInputLineMode,
+    _description: *const c_char,
+    _char_max: uint32,
+    existing_text: *const c_char,
+) -> bool {
+    let existing_text = string(existing_text);
+    let mut state = state();
+    if state.gamepad_text_input.is_some() {
+        return false;
+    }
+    state.gamepad_text_input = Some(existing_text);
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetEnteredGamepadTextLength(_: *mut ISteamUtils) -> uint32 {
+    state()
+        .entered_gamepad_text
+        .as_ref()
+        .map_or(0, |t| t.as_bytes_with_nul().len() as uint32)
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetEnteredGamepadTextInput(
+    _: *mut ISteamUtils,
+    text: *mut c_char,
+    text_len: uint32,
+) -> bool {
+    match &state().entered_gamepad_text {
+        Some(entered) => {
+            copy_string(text, text_len as usize, &entered.to_string_lossy());
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_ShowFloatingGamepadTextInput(
+    _: *mut ISteamUtils,
+    _mode: EFloatingGamepadTextInputMode,
+    _x: c_int,
+    _y: c_int,
+    _width: c_int,
+    _height: c_int,
+) -> bool {
+    state().floating_text_input = true;
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_DismissFloatingGamepadTextInput(_: *mut ISteamUtils) -> bool {
+    let mut state = state();
+    if !std::mem::replace(&mut state.floating_text_input, false) {
+        return false;
+    }
+    state.post(
+        FloatingGamepadTextInputDismissed_t_k_iCallback as i32,
+        &FloatingGamepadTextInputDismissed_t { _address: 0 },
+    );
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_InitFilterText(_: *mut ISteamUtils, _options: uint32) -> bool {
+    true
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_FilterText(
+    _: *mut ISteamUtils,
+    _context: ETextFilteringContext,
+    _source: uint64_steamid,
+    input: *const c_char,
+    output: *mut c_char,
+    output_len: uint32,
+) -> c_int {
+    let mut text = string(input);
+    let mut filtered = 0;
+    for word in &state().filtered_words {
+        filtered += text.matches(word.as_str()).count();
+        text = text.replace(word.as_str(), &"*".repeat(word.len()));
+    }
+    copy_string(output, output_len as usize, &text);
+    filtered as c_int
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetImageSize(
+    _: *mut ISteamUtils,
+    image: c_int,
+    width: *mut uint32,
+    height: *mut uint32,
+) -> bool {
+    match state().images.get(&image) {
+        Some(image) => {
+            *width = image.width;
+            *height = image.height;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetImageRGBA(
+    _: *mut ISteamUtils,
+    image: c_int,
+    dest: *mut uint8,
+    dest_len: c_int,
+) -> bool {
+    match state().images.get(&image) {
+        Some(image) if image.rgba.len() <= dest_len as usize => {
+            ptr::copy_nonoverlapping(image.rgba.as_ptr(), dest, image.rgba.len());
+            true
+        }
+        _ => false,
+    }
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_IsAPICallCompleted(
+    _: *mut ISteamUtils,
+    api_call: SteamAPICall_t,
+    failed: *mut bool,
+) -> bool {
+    *failed = false;
+    state().call_results.contains_key(&api_call)
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_GetAPICallResult(
+    _: *mut ISteamUtils,
+    api_call: SteamAPICall_t,
+    callback: *mut c_void,
+    callback_len: c_int,
+    expected: c_int,
+    failed: *mut bool,
+) -> bool {
+    take_call_result(api_call, callback, callback_len, expected, failed)
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_SetOverlayNotificationPosition(
+    _: *mut ISteamUtils,
+    _position: ENotificationPosition,
+) {
+}
+
+pub unsafe fn SteamAPI_ISteamUtils_SetWarningMessageHook(
+    _: *mut ISteamUtils,
+    _function: SteamAPIWarningMessageHook_t,
+) {
+}
+
+// Not simulated
+
+/// Defines functions that panic when called, for the parts of the api
+/// the fake doesn't simulate
+macro_rules! not_simulated {
+    ($(pub fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*) => {
+        $(
+            pub unsafe fn $name($(_: $ty),*) $(-> $ret)? {
+                panic!(concat!(stringify!($name), " is not simulated by the fake backend"))
+            }
+        )*
+    };
+}
+
+not_simulated! {
+    pub fn SteamAPI_ISteamGameServer_BeginAuthSession(
+        self_: *mut ISteamGameServer,
+        pAuthTicket: *const c_void,
+        cbAuthTicket: c_int,
+        steamID: uint64_steamid,
+    ) -> EBeginAuthSessionResult;
+    pub fn SteamAPI_ISteamGameServer_CancelAuthTicket(
+        self_: *mut ISteamGameServer,
+        hAuthTicket: HAuthTicket,
+    );
+    pub fn SteamAPI_ISteamGameServer_EndAuthSession(
+        self_: *mut ISteamGameServer,
+        steamID: uint64_steamid,
+    );
+    pub fn SteamAPI_ISteamGameServer_GetAuthSessionTicket(
+        self_: *mut ISteamGameServer,
+        pTicket: *mut c_void,
+        cbMaxTicket: c_int,
+        pcbTicket: *mut uint32,
+    ) -> HAuthTicket;
+    pub fn SteamAPI_ISteamGameServer_GetSteamID(self_: *mut ISteamGameServer) -> uint64_steamid;
+    pub fn SteamAPI_ISteamGameServer_LogOnAnonymous(self_: *mut ISteamGameServer);
+    pub fn SteamAPI_ISteamGameServer_SetAdvertiseServerActive(
+        self_: *mut ISteamGameServer,
+        bActive: bool,
+    );
+    pub fn SteamAPI_ISteamGameServer_SetDedicatedServer(
+        self_: *mut ISteamGameServer,
+        bDedicated: bool,
+    );
+    pub fn SteamAPI_ISteamGameServer_SetGameDescription(
+        self_: *mut ISteamGameServer,
+        pszGameDescription: *const c_char,
+    );
+    pub fn SteamAPI_ISteamGameServer_SetMapName(
+        self_: *mut ISteamGameServer,
+        pszMapName: *const c_char,
+    );
+    pub fn SteamAPI_ISteamGameServer_SetMaxPlayerCount(
+        self_: *mut ISteamGameServer,
+        cPlayersMax: c_int,
+    );
+    pub fn SteamAPI_ISteamGameServer_SetModDir(
+        self_: *mut ISteamGameServer,
+        pszModDir: *const c_char,
+    );
+    pub fn SteamAPI_ISteamGameServer_SetProduct(
+        self_: *mut ISteamGameServer,
+        pszProduct: *const c_char,
+    );
+    pub fn SteamAPI_ISteamInput_ActivateActionSet(
+        self_: *mut ISteamInput,
+        inputHandle: InputHandle_t,
+        actionSetHandle: InputActionSetHandle_t,
+    );
+    pub fn SteamAPI_ISteamInput_GetActionSetHandle(
+        self_: *mut ISteamInput,
+        pszActionSetName: *const c_char,
+    ) -> InputActionSetHandle_t;
+    pub fn SteamAPI_ISteamInput_GetAnalogActionData(
+        self_: *mut ISteamInput,
+        inputHandle: InputHandle_t,
+        analogActionHandle: InputAnalogActionHandle_t,
+    ) -> InputAnalogActionData_t;
+    pub fn SteamAPI_ISteamInput_GetAnalogActionHandle(
+        self_: *mut ISteamInput,
+        pszActionName: *const c_char,
+    ) -> InputAnalogActionHandle_t;
+    pub fn SteamAPI_ISteamInput_GetConnectedControllers(
+        self_: *mut ISteamInput,
+        handlesOut: *mut InputHandle_t,
+    ) -> c_int;
+    pub fn SteamAPI_ISteamInput_GetDigitalActionData(
+        self_: *mut ISteamInput,
+        inputHandle: InputHandle_t,
+        digitalActionHandle: InputDigitalActionHandle_t,
+    ) -> InputDigitalActionData_t;
+    pub fn SteamAPI_ISteamInput_GetDigitalActionHandle(
+        self_: *mut ISteamInput,
+        pszActionName: *const c_char,
+    ) -> InputDigitalActionHandle_t;
+    pub fn SteamAPI_ISteamInput_Init(
+        self_: *mut ISteamInput,
+        bExplicitlyCallRunFrame: bool,
+    ) -> bool;
+    pub fn SteamAPI_ISteamInput_RunFrame(self_: *mut ISteamInput, bReservedValue: bool);
+    pub fn SteamAPI_ISteamInput_Shutdown(self_: *mut ISteamInput) -> bool;
+    pub fn SteamAPI_ISteamNetworkingMessages_AcceptSessionWithUser(
+        self_: *mut ISteamNetworkingMessages,
+        identityRemote: *const SteamNetworkingIdentity,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingMessages_CloseSessionWithUser(
+        self_: *mut ISteamNetworkingMessages,
+        identityRemote: *const SteamNetworkingIdentity,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingSockets_AcceptConnection(
+        self_: *mut ISteamNetworkingSockets,
+        hConn: HSteamNetConnection,
+    ) -> EResult;
+    pub fn SteamAPI_ISteamNetworkingSockets_CloseConnection(
+        self_: *mut ISteamNetworkingSockets,
+        hPeer: HSteamNetConnection,
+        nReason: c_int,
+        pszDebug: *const c_char,
+        bEnableLinger: bool,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingSockets_CloseListenSocket(
+        self_: *mut ISteamNetworkingSockets,
+        hSocket: HSteamListenSocket,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingSockets_ConnectByIPAddress(
+        self_: *mut ISteamNetworkingSockets,
+        address: *const SteamNetworkingIPAddr,
+        nOptions: c_int,
+        pOptions: *const SteamNetworkingConfigValue_t,
+    ) -> HSteamNetConnection;
+    pub fn SteamAPI_ISteamNetworkingSockets_ConnectP2P(
+        self_: *mut ISteamNetworkingSockets,
+        identityRemote: *const SteamNetworkingIdentity,
+        nRemoteVirtualPort: c_int,
+        nOptions: c_int,
+        pOptions: *const SteamNetworkingConfigValue_t,
+    ) -> HSteamNetConnection;
+    pub fn SteamAPI_ISteamNetworkingSockets_CreateHostedDedicatedServerListenSocket(
+        self_: *mut ISteamNetworkingSockets,
+        nLocalVirtualPort: c_int,
+        nOptions: c_int,
+        pOptions: *const SteamNetworkingConfigValue_t,
+    ) -> HSteamListenSocket;
+    pub fn SteamAPI_ISteamNetworkingSockets_CreateListenSocketIP(
+        self_: *mut ISteamNetworkingSockets,
+        localAddress: *const SteamNetworkingIPAddr,
+        nOptions: c_int,
+        pOptions: *const SteamNetworkingConfigValue_t,
+    ) -> HSteamListenSocket;
+    pub fn SteamAPI_ISteamNetworkingSockets_CreateListenSocketP2P(
+        self_: *mut ISteamNetworkingSockets,
+        nLocalVirtualPort: c_int,
+        nOptions: c_int,
+        pOptions: *const SteamNetworkingConfigValue_t,
+    ) -> HSteamListenSocket;
+    pub fn SteamAPI_ISteamNetworkingSockets_CreatePollGroup(
+        self_: *mut ISteamNetworkingSockets,
+    ) -> HSteamNetPollGroup;
+    pub fn SteamAPI_ISteamNetworkingSockets_DestroyPollGroup(
+        self_: *mut ISteamNetworkingSockets,
+        hPollGroup: HSteamNetPollGroup,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingSockets_FlushMessagesOnConnection(
+        self_: *mut ISteamNetworkingSockets,
+        hConn: HSteamNetConnection,
+    ) -> EResult;
+    pub fn SteamAPI_ISteamNetworkingSockets_GetConnectionUserData(
+        self_: *mut ISteamNetworkingSockets,
+        hPeer: HSteamNetConnection,
+    ) -> int64;
+    pub fn SteamAPI_ISteamNetworkingSockets_InitAuthentication(
+        self_: *mut ISteamNetworkingSockets,
+    ) -> ESteamNetworkingAvailability;
+    pub fn SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnConnection(
+        self_: *mut ISteamNetworkingSockets,
+        hConn: HSteamNetConnection,
+        ppOutMessages: *mut *mut SteamNetworkingMessage_t,
+        nMaxMessages: c_int,
+    ) -> c_int;
+    pub fn SteamAPI_ISteamNetworkingSockets_ReceiveMessagesOnPollGroup(
+        self_: *mut ISteamNetworkingSockets,
+        hPollGroup: HSteamNetPollGroup,
+        ppOutMessages: *mut *mut SteamNetworkingMessage_t,
+        nMaxMessages: c_int,
+    ) -> c_int;
+    pub fn SteamAPI_ISteamNetworkingSockets_SendMessageToConnection(
+        self_: *mut ISteamNetworkingSockets,
+        hConn: HSteamNetConnection,
+        pData: *const c_void,
+        cbData: uint32,
+        nSendFlags: c_int,
+        pOutMessageNumber: *mut int64,
+    ) -> EResult;
+    pub fn SteamAPI_ISteamNetworkingSockets_SendMessages(
+        self_: *mut ISteamNetworkingSockets,
+        nMessages: c_int,
+        pMessages: *const *mut SteamNetworkingMessage_t,
+        pOutMessageNumberOrResult: *mut int64,
+    );
+    pub fn SteamAPI_ISteamNetworkingSockets_SetConnectionName(
+        self_: *mut ISteamNetworkingSockets,
+        hPeer: HSteamNetConnection,
+        pszName: *const c_char,
+    );
+    pub fn SteamAPI_ISteamNetworkingSockets_SetConnectionPollGroup(
+        self_: *mut ISteamNetworkingSockets,
+        hConn: HSteamNetConnection,
+        hPollGroup: HSteamNetPollGroup,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingSockets_SetConnectionUserData(
+        self_: *mut ISteamNetworkingSockets,
+        hPeer: HSteamNetConnection,
+        nUserData: int64,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworkingUtils_AllocateMessage(
+        self_: *mut ISteamNetworkingUtils,
+        cbAllocateBuffer: c_int,
+    ) -> *mut SteamNetworkingMessage_t;
+    pub fn SteamAPI_ISteamNetworkingUtils_GetRelayNetworkStatus(
+        self_: *mut ISteamNetworkingUtils,
+        pDetails: *mut SteamRelayNetworkStatus_t,
+    ) -> ESteamNetworkingAvailability;
+    pub fn SteamAPI_ISteamNetworkingUtils_InitRelayNetworkAccess(self_: *mut ISteamNetworkingUtils);
+    pub fn SteamAPI_ISteamNetworkingUtils_SetDebugOutputFunction(
+        self_: *mut ISteamNetworkingUtils,
+        eDetailLevel: ESteamNetworkingSocketsDebugOutputType,
+        pfnFunc: FSteamNetworkingSocketsDebugOutput,
+    );
+    pub fn SteamAPI_ISteamNetworking_AcceptP2PSessionWithUser(
+        self_: *mut ISteamNetworking,
+        steamIDRemote: uint64_steamid,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworking_CloseP2PSessionWithUser(
+        self_: *mut ISteamNetworking,
+        steamIDRemote: uint64_steamid,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworking_IsP2PPacketAvailable(
+        self_: *mut ISteamNetworking,
+        pcubMsgSize: *mut uint32,
+        nChannel: c_int,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworking_ReadP2PPacket(
+        self_: *mut ISteamNetworking,
+        pubDest: *mut c_void,
+        cubDest: uint32,
+        pcubMsgSize: *mut uint32,
+        psteamIDRemote: *mut CSteamID,
+        nChannel: c_int,
+    ) -> bool;
+    pub fn SteamAPI_ISteamNetworking_SendP2PPacket(
+        self_: *mut ISteamNetworking,
+        steamIDRemote: uint64_steamid,
+        pubData: *const c_void,
+        cubData: uint32,
+        eP2PSendType: EP2PSend,
+        nChannel: c_int,
+    ) -> bool;
+    pub fn SteamAPI_SteamGameServerUGC_v016() -> *mut ISteamUGC;
+    pub fn SteamAPI_SteamGameServerUtils_v010() -> *mut ISteamUtils;
+    pub fn SteamAPI_SteamGameServer_v014() -> *mut ISteamGameServer;
+    pub fn SteamAPI_SteamInput_v006() -> *mut ISteamInput;
+    pub fn SteamAPI_SteamNetworkingSockets_SteamAPI_v012() -> *mut ISteamNetworkingSockets;
+    pub fn SteamAPI_SteamNetworkingUtils_SteamAPI_v004() -> *mut ISteamNetworkingUtils;
+    pub fn SteamAPI_SteamNetworking_v006() -> *mut ISteamNetworking;
+    pub fn SteamGameServer_GetHSteamPipe() -> HSteamPipe;
+    pub fn SteamGameServer_Shutdown();
+    pub fn SteamInternal_GameServer_Init(
+        unIP: uint32,
+        usLegacySteamPort: uint16,
+        usGamePort: uint16,
+        usQueryPort: uint16,
+        eServerMode: EServerMode,
+        pchVersionString: *const c_char,
+    ) -> bool;
+}
@@ -1,5 +1,9 @@
 use super::*;
+use std::future::Future;
 use std::net::Ipv4Addr;
+use std::os::raw::c_int;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
 
 const CALLBACK_BASE_ID: i32 = 300;
 
@@ -46,6 +50,24 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Restrictions on what the current user may do, e.g. due to
+    /// parental settings
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct UserRestrictions: u32 {
+        const NONE         = 0x0000;
+        /// Not logged in or the restrictions aren't known yet
+        const UNKNOWN      = 0x0001;
+        const ANY_CHAT     = 0x0002;
+        const VOICE_CHAT   = 0x0004;
+        const GROUP_CHAT   = 0x0008;
+        /// Limited by the rating of the current game
+        const RATING       = 0x0010;
+        const GAME_INVITES = 0x0020;
+        const TRADING      = 0x0040;
+    }
+}
+
 /// Access to the steam friends interface
 pub struct Friends<Manager> {
     pub(crate) friends: *mut sys::ISteamFriends,
@@ -78,7 +100,7 @@ impl<Manager> Friends<Manager> {
                 friends.push(Friend {
                     id: friend,
                     friends: self.friends,
-                    _inner: self.inner.clone(),
+                    inner: self.inner.clone(),
                 });
             }
 
@@ -90,18 +112,82 @@ impl<Manager> Friends<Manager> {
         Friend {
             id: friend,
             friends: self.friends,
-            _inner: self.inner.clone(),
+            inner: self.inner.clone(),
         }
     }
 
-    pub fn request_user_information(&self, user: SteamId, name_only: bool) -> bool {
+    /// Returns the persona state of the current user
+    pub fn persona_state(&self) -> FriendState {
+        unsafe { FriendState::from_raw(sys::SteamAPI_ISteamFriends_GetPersonaState(self.friends)) }
+    }
+
+    /// Changes the persona name of the current user.
+    ///
+    /// The callback is passed whether the name was changed on the steam
+    /// servers.
+    pub fn set_persona_name<F>(&self, name: &str, cb: F) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(SResult<()>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.set_persona_name_call(name, cb))
+    }
+
+    /// Async version of [`set_persona_name`](#method.set_persona_name)
+    pub fn set_persona_name_async(&self, name: &str) -> CallResultFuture<SResult<()>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| self.set_persona_name_call(name, cb))
+    }
+
+    fn set_persona_name_call<F>(&self, name: &str, cb: F) -> sys::SteamAPICall_t
+    where
+        F: FnOnce(SResult<()>) + 'static + Send,
+    {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let api_call = sys::SteamAPI_ISteamFriends_SetPersonaName(self.friends, name.as_ptr());
+            register_call_result::<sys::SetPersonaNameResponse_t, _, _>(
+                &self.inner,
+                api_call,
+                CALLBACK_BASE_ID + 47,
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if !v.m_bSuccess => Err(v.m_result.into()),
+                        Ok(_) => Ok(()),
+                    })
+                },
+            );
+            api_call
+        }
+    }
+
+    /// Returns the restrictions on what the current user may do
+    pub fn user_restrictions(&self) -> UserRestrictions {
         unsafe {
+            UserRestrictions::from_bits_truncate(sys::SteamAPI_ISteamFriends_GetUserRestrictions(
+                self.friends,
+            ))
+        }
+    }
+
+    /// Requests the persona name and, unless `name_only` is set, the
+    /// avatar of a user.
+    ///
+    /// If the information isn't cached yet `PersonaStateChange` is posted
+    /// once it has been fetched.
+    pub fn request_user_information(&self, user: SteamId, name_only: bool) -> UserInformation {
+        let requested = unsafe {
             sys::SteamAPI_ISteamFriends_RequestUserInformation(self.friends, user.0, name_only)
+        };
+        if requested {
+            UserInformation::Requested
+        } else {
+            UserInformation::Cached
         }
     }
 
-    pub fn activate_game_overlay(&self, dialog: &str) {
-        let dialog = CString::new(dialog).unwrap();
+    /// Opens the steam overlay on the given dialog
+    pub fn activate_game_overlay(&self, dialog: OverlayDialog) {
+        let dialog = CString::new(dialog.as_str()).unwrap();
         unsafe {
             sys::SteamAPI_ISteamFriends_ActivateGameOverlay(
                 self.friends,
@@ -110,14 +196,51 @@ impl<Manager> Friends<Manager> {
         }
     }
 
+    /// Opens the steam overlay on a dialog about the given user
+    pub fn activate_game_overlay_to_user(&self, dialog: OverlayUserDialog, user: SteamId) {
+        let dialog = CString::new(dialog.as_str()).unwrap();
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayToUser(
+                self.friends,
+                dialog.as_ptr() as *const _,
+                user.0,
+            );
+        }
+    }
+
+    /// Opens the steam overlay on the store page of an app
+    pub fn activate_game_overlay_to_store(&self, app_id: AppId, flag: OverlayToStoreFlag) {
+        let flag = match flag {
+            OverlayToStoreFlag::None => sys::EOverlayToStoreFlag::k_EOverlayToStoreFlag_None,
+            OverlayToStoreFlag::AddToCart => {
+                sys::EOverlayToStoreFlag::k_EOverlayToStoreFlag_AddToCart
+            }
+            OverlayToStoreFlag::AddToCartAndShow => {
+                sys::EOverlayToStoreFlag::k_EOverlayToStoreFlag_AddToCartAndShow
+            }
+        };
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayToStore(self.friends, app_id.0, flag);
+        }
+    }
+
     // I don't know why this is part of friends either
-    pub fn activate_game_overlay_to_web_page(&self, url: &str) {
+    /// Opens the steam overlay's web browser on the given url
+    pub fn activate_game_overlay_to_web_page(&self, url: &str, mode: OverlayWebPageMode) {
+        let mode = match mode {
+            OverlayWebPageMode::Default => {
+                sys::EActivateGameOverlayToWebPageMode::k_EActivateGameOverlayToWebPageMode_Default
+            }
+            OverlayWebPageMode::Modal => {
+                sys::EActivateGameOverlayToWebPageMode::k_EActivateGameOverlayToWebPageMode_Modal
+            }
+        };
         unsafe {
             let url = CString::new(url).unwrap();
             sys::SteamAPI_ISteamFriends_ActivateGameOverlayToWebPage(
                 self.friends,
                 url.as_ptr() as *const _,
-                sys::EActivateGameOverlayToWebPageMode::k_EActivateGameOverlayToWebPageMode_Default,
+                mode,
             );
         }
     }
@@ -129,21 +252,256 @@ impl<Manager> Friends<Manager> {
         }
     }
 
+    /// Opens up a Remote Play Together invite dialog for the given lobby
+    pub fn activate_remote_play_together_invite_dialog(&self, lobby: LobbyId) {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayRemotePlayTogetherInviteDialog(
+                self.friends,
+                lobby.0,
+            );
+        }
+    }
+
+    /// Opens up an invite dialog that sends the given connect string to
+    /// the invited friends, for games that don't use lobbies.
+    ///
+    /// The invited friend receives it as `GameRichPresenceJoinRequested`
+    /// if the game is running, or as the launch command line otherwise.
+    pub fn activate_invite_dialog_connect_string(&self, connect: &str) {
+        let connect = CString::new(connect).unwrap();
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ActivateGameOverlayInviteDialogConnectString(
+                self.friends,
+                connect.as_ptr(),
+            );
+        }
+    }
+
+    /// Invites a friend to the current game with the given connect string.
+    ///
+    /// The friend receives it as `GameRichPresenceJoinRequested` if the game
+    /// is running, or as the launch command line otherwise.
+    pub fn invite_user_to_game(&self, friend: SteamId, connect: &str) -> bool {
+        let connect = CString::new(connect).unwrap();
+        unsafe {
+            sys::SteamAPI_ISteamFriends_InviteUserToGame(self.friends, friend.0, connect.as_ptr())
+        }
+    }
+
     /// Set rich presence for the user. Unsets the rich presence if `value` is None or empty.
     /// See [Steam API](https://partner.steamgames.com/doc/api/ISteamFriends#SetRichPresence)
     pub fn set_rich_presence(&self, key: &str, value: Option<&str>) -> bool {
         unsafe {
             let key = CString::new(key).unwrap_or_default();
+            // Bound here, `map` would drop the string before steam reads it
+            let value = value.and_then(|v| CString::new(v).ok());
             sys::SteamAPI_ISteamFriends_SetRichPresence(
                 self.friends,
                 key.as_ptr() as *const _,
-                value
-                    .and_then(|v| CString::new(v).ok())
-                    .map(|s| s.as_ptr() as *const _)
-                    .unwrap_or(std::ptr::null()),
+                value.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
             )
         }
     }
+
+    /// Sets or unsets the rich presence keys covered by `RichPresence`.
+    ///
+    /// Returns `false` if any of the keys couldn't be set.
+    pub fn set_rich_presence_info(&self, info: &RichPresence) -> bool {
+        let group_size = info.player_group_size.map(|v| v.to_string());
+        [
+            (RichPresence::STATUS, info.status.as_deref()),
+            (RichPresence::CONNECT, info.connect.as_deref()),
+            (RichPresence::DISPLAY, info.display.as_deref()),
+            (RichPresence::PLAYER_GROUP, info.player_group.as_deref()),
+            (RichPresence::PLAYER_GROUP_SIZE, group_size.as_deref()),
+        ]
+        .iter()
+        .fold(true, |ok, &(key, value)| {
+            self.set_rich_presence(key, value) && ok
+        })
+    }
+
+    /// Clears all of the current user's rich presence keys
+    pub fn clear_rich_presence(&self) {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_ClearRichPresence(self.friends);
+        }
+    }
+
+    /// Requests the rich presence of a user that isn't a friend, such as
+    /// another player in the same lobby.
+    ///
+    /// `FriendRichPresenceUpdate` is posted once it is available. The rich
+    /// presence of friends is always kept up to date.
+    pub fn request_friend_rich_presence(&self, user: SteamId) {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_RequestFriendRichPresence(self.friends, user.0);
+        }
+    }
+}
+
+/// Whether `Friends::request_user_information` had to fetch the
+/// information from steam
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserInformation {
+    /// The information is already available
+    Cached,
+    /// The information is being fetched, `PersonaStateChange` is posted
+    /// once it is available
+    Requested,
+}
+
+/// A dialog of the steam overlay
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlayDialog {
+    Friends,
+    Community,
+    Players,
+    Settings,
+    OfficialGameGroup,
+    Stats,
+    Achievements,
+}
+
+impl OverlayDialog {
+    /// Returns the name steam uses for the dialog
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverlayDialog::Friends => "friends",
+            OverlayDialog::Community => "community",
+            OverlayDialog::Players => "players",
+            OverlayDialog::Settings => "settings",
+            OverlayDialog::OfficialGameGroup => "officialgamegroup",
+            OverlayDialog::Stats => "stats",
+            OverlayDialog::Achievements => "achievements",
+        }
+    }
+}
+
+/// A dialog of the steam overlay about a specific user
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlayUserDialog {
+    /// The user's profile
+    SteamId,
+    /// A chat with the user
+    Chat,
+    /// A trade with the user
+    JoinTrade,
+    /// The user's stats
+    Stats,
+    /// The user's achievements
+    Achievements,
+    /// Asks to add the user as a friend
+    FriendAdd,
+    /// Asks to remove the user as a friend
+    FriendRemove,
+    /// Asks to accept the user's friend request
+    FriendRequestAccept,
+    /// Asks to ignore the user's friend request
+    FriendRequestIgnore,
+}
+
+impl OverlayUserDialog {
+    /// Returns the name steam uses for the dialog
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverlayUserDialog::SteamId => "steamid",
+            OverlayUserDialog::Chat => "chat",
+            OverlayUserDialog::JoinTrade => "jointrade",
+            OverlayUserDialog::Stats => "stats",
+            OverlayUserDialog::Achievements => "achievements",
+            OverlayUserDialog::FriendAdd => "friendadd",
+            OverlayUserDialog::FriendRemove => "friendremove",
+            OverlayUserDialog::FriendRequestAccept => "friendrequestaccept",
+            OverlayUserDialog::FriendRequestIgnore => "friendrequestignore",
+        }
+    }
+}
+
+/// What to do with the app when opening its store page in the overlay
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlayToStoreFlag {
+    None,
+    /// Adds the app to the user's cart
+    AddToCart,
+    /// Adds the app to the user's cart and shows the cart
+    AddToCartAndShow,
+}
+
+/// How a web page is opened in the overlay
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlayWebPageMode {
+    /// The browser opens next to the other overlay windows, and stays
+    /// open when the overlay is closed and reopened
+    Default,
+    /// The browser opens on its own and the overlay closes when the
+    /// browser window is closed
+    Modal,
+}
+
+/// The rich presence keys with special meaning to steam
+///
+/// Any other key is only visible to the game itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RichPresence {
+    /// A UTF-8 status shown in the "view game info" dialog
+    pub status: Option<String>,
+    /// The command line used to join the user, see `LaunchCommands`
+    pub connect: Option<String>,
+    /// The localization token from the app's rich presence files that is
+    /// shown in the friends list
+    pub display: Option<String>,
+    /// Groups users that are playing together in the friends list
+    pub player_group: Option<String>,
+    /// The total number of players in the group, shown next to it
+    pub player_group_size: Option<u32>,
+}
+
+impl RichPresence {
+    pub const STATUS: &'static str = "status";
+    pub const CONNECT: &'static str = "connect";
+    pub const DISPLAY: &'static str = "steam_display";
+    pub const PLAYER_GROUP: &'static str = "steam_player_group";
+    pub const PLAYER_GROUP_SIZE: &'static str = "steam_player_group_size";
+
+    /// Parses the commands in the `connect` key
+    pub fn launch_commands(&self) -> LaunchCommands {
+        LaunchCommands::parse(self.connect.as_deref().unwrap_or_default())
+    }
+
+    fn from_keys(mut get: impl FnMut(&str) -> Option<String>) -> RichPresence {
+        RichPresence {
+            status: get(RichPresence::STATUS),
+            connect: get(RichPresence::CONNECT),
+            display: get(RichPresence::DISPLAY),
+            player_group: get(RichPresence::PLAYER_GROUP),
+            player_group_size: get(RichPresence::PLAYER_GROUP_SIZE).and_then(|v| v.parse().ok()),
+        }
+    }
+}
+
+/// Called when the rich presence of a friend, or a user requested with
+/// `Friends::request_friend_rich_presence`, changes
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FriendRichPresenceUpdate {
+    pub friend: SteamId,
+    /// The app the rich presence is for
+    pub app_id: AppId,
+}
+
+unsafe impl Callback for FriendRichPresenceUpdate {
+    const ID: i32 = CALLBACK_BASE_ID + 36;
+    const SIZE: i32 = ::std::mem::size_of::<sys::FriendRichPresenceUpdate_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::FriendRichPresenceUpdate_t);
+        FriendRichPresenceUpdate {
+            friend: SteamId(val.m_steamIDFriend.m_steamid.m_unAll64Bits),
+            app_id: AppId(val.m_nAppID),
+        }
+    }
 }
 
 /// Information about a friend's current state in a game
@@ -203,10 +561,98 @@ unsafe impl Callback for GameLobbyJoinRequested {
     }
 }
 
+/// Called when the user accepts an invite or joins a friend through their
+/// rich presence `connect` key while the game is running
+///
+/// When the game isn't running it is started with the connect string as
+/// its command line instead.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRichPresenceJoinRequested {
+    /// The friend that was joined, or the one that sent the invite
+    pub friend: SteamId,
+    /// The connect string from the rich presence or invite
+    pub connect: String,
+}
+
+impl GameRichPresenceJoinRequested {
+    /// Parses the commands in the connect string
+    pub fn launch_commands(&self) -> LaunchCommands {
+        LaunchCommands::parse(&self.connect)
+    }
+}
+
+unsafe impl Callback for GameRichPresenceJoinRequested {
+    const ID: i32 = CALLBACK_BASE_ID + 37;
+    const SIZE: i32 = ::std::mem::size_of::<sys::GameRichPresenceJoinRequested_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GameRichPresenceJoinRequested_t);
+        GameRichPresenceJoinRequested {
+            friend: SteamId(val.m_steamIDFriend.m_steamid.m_unAll64Bits),
+            // The buffer isn't guaranteed to be terminated if it is full
+            connect: string_from_buffer(&val.m_rgchConnect),
+        }
+    }
+}
+
+/// Called when the steam overlay is opened or closed
+///
+/// Games should pause while the overlay is open.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameOverlayActivated {
+    /// Whether the overlay is now open
+    pub active: bool,
+}
+
+unsafe impl Callback for GameOverlayActivated {
+    const ID: i32 = CALLBACK_BASE_ID + 31;
+    const SIZE: i32 = ::std::mem::size_of::<sys::GameOverlayActivated_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GameOverlayActivated_t);
+        GameOverlayActivated {
+            active: val.m_bActive != 0,
+        }
+    }
+}
+
+/// Called when an avatar image has been loaded.
+///
+/// Avatars that aren't cached yet are loaded after requesting them
+/// with `Friend::small_avatar` and friends, after which they return
+/// the image.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AvatarImageLoaded {
+    /// The user whose avatar was loaded
+    pub steam_id: SteamId,
+    /// The image handle of the avatar, for `Utils::image`
+    pub image_handle: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+unsafe impl Callback for AvatarImageLoaded {
+    const ID: i32 = CALLBACK_BASE_ID + 34;
+    const SIZE: i32 = ::std::mem::size_of::<sys::AvatarImageLoaded_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::AvatarImageLoaded_t);
+        AvatarImageLoaded {
+            steam_id: SteamId(val.m_steamID.m_steamid.m_unAll64Bits),
+            image_handle: val.m_iImage,
+            width: val.m_iWide as u32,
+            height: val.m_iTall as u32,
+        }
+    }
+}
+
 pub struct Friend<Manager> {
     id: SteamId,
     friends: *mut sys::ISteamFriends,
-    _inner: Arc<Inner<Manager>>,
+    inner: Arc<Inner<Manager>>,
 }
 
 impl<Manager> Debug for Friend<Manager> {
@@ -230,20 +676,132 @@ impl<Manager> Friend<Manager> {
 
     pub fn state(&self) -> FriendState {
         unsafe {
-            let state = sys::SteamAPI_ISteamFriends_GetFriendPersonaState(self.friends, self.id.0);
-            match state {
-                sys::EPersonaState::k_EPersonaStateOffline => FriendState::Offline,
-                sys::EPersonaState::k_EPersonaStateOnline => FriendState::Online,
-                sys::EPersonaState::k_EPersonaStateBusy => FriendState::Busy,
-                sys::EPersonaState::k_EPersonaStateAway => FriendState::Away,
-                sys::EPersonaState::k_EPersonaStateSnooze => FriendState::Snooze,
-                sys::EPersonaState::k_EPersonaStateLookingToPlay => FriendState::LookingToPlay,
-                sys::EPersonaState::k_EPersonaStateLookingToTrade => FriendState::LookingToTrade,
-                _ => unreachable!(),
+            FriendState::from_raw(sys::SteamAPI_ISteamFriends_GetFriendPersonaState(
+                self.friends,
+                self.id.0,
+            ))
+        }
+    }
+
+    /// Returns the nickname the current user has given this user, if any
+    pub fn nickname(&self) -> Option<String> {
+        unsafe {
+            let nickname = sys::SteamAPI_ISteamFriends_GetPlayerNickname(self.friends, self.id.0);
+            if nickname.is_null() {
+                return None;
             }
+            Some(CStr::from_ptr(nickname).to_string_lossy().into_owned())
         }
     }
 
+    /// Returns the steam level of the user.
+    ///
+    /// This is `0` until the level has been fetched, which happens for
+    /// friends and users requested with `Friends::request_user_information`.
+    /// `PersonaStateChange` with `PersonaChange::STEAM_LEVEL` is posted
+    /// when it changes.
+    pub fn steam_level(&self) -> u32 {
+        unsafe {
+            sys::SteamAPI_ISteamFriends_GetFriendSteamLevel(self.friends, self.id.0).max(0) as u32
+        }
+    }
+
+    /// Returns the relationship of the current user to this user
+    pub fn relationship(&self) -> FriendRelationship {
+        unsafe {
+            let relationship =
+                sys::SteamAPI_ISteamFriends_GetFriendRelationship(self.friends, self.id.0);
+            match relationship {
+                sys::EFriendRelationship::k_EFriendRelationshipBlocked => {
+                    FriendRelationship::Blocked
+                }
+                sys::EFriendRelationship::k_EFriendRelationshipRequestRecipient => {
+                    FriendRelationship::RequestRecipient
+                }
+                sys::EFriendRelationship::k_EFriendRelationshipFriend => FriendRelationship::Friend,
+                sys::EFriendRelationship::k_EFriendRelationshipRequestInitiator => {
+                    FriendRelationship::RequestInitiator
+                }
+                sys::EFriendRelationship::k_EFriendRelationshipIgnored => {
+                    FriendRelationship::Ignored
+                }
+                sys::EFriendRelationship::k_EFriendRelationshipIgnoredFriend => {
+                    FriendRelationship::IgnoredFriend
+                }
+                _ => FriendRelationship::None,
+            }
+        }
+    }
+
+    /// Returns one of the user's previous persona names, with `0` being
+    /// the most recent one.
+    ///
+    /// Returns `None` past the end of the history.
+    pub fn persona_name_history(&self, index: u32) -> Option<String> {
+        unsafe {
+            let name = sys::SteamAPI_ISteamFriends_GetFriendPersonaNameHistory(
+                self.friends,
+                self.id.0,
+                index as c_int,
+            );
+            if name.is_null() {
+                return None;
+            }
+            let name = CStr::from_ptr(name).to_string_lossy();
+            if name.is_empty() {
+                None
+            } else {
+                Some(name.into_owned())
+            }
+        }
+    }
+
+    /// Returns the value of one of the friend's rich presence keys for
+    /// the current game.
+    ///
+    /// Returns `None` if the key isn't set.
+    pub fn rich_presence(&self, key: &str) -> Option<String> {
+        let key = CString::new(key).ok()?;
+        unsafe {
+            let value = sys::SteamAPI_ISteamFriends_GetFriendRichPresence(
+                self.friends,
+                self.id.0,
+                key.as_ptr(),
+            );
+            let value = CStr::from_ptr(value).to_string_lossy();
+            if value.is_empty() {
+                None
+            } else {
+                Some(value.into_owned())
+            }
+        }
+    }
+
+    /// Returns the rich presence keys the friend has set for the current
+    /// game
+    pub fn rich_presence_keys(&self) -> Vec<String> {
+        unsafe {
+            let count =
+                sys::SteamAPI_ISteamFriends_GetFriendRichPresenceKeyCount(self.friends, self.id.0);
+            (0..count)
+                .map(|idx| {
+                    let key = sys::SteamAPI_ISteamFriends_GetFriendRichPresenceKeyByIndex(
+                        self.friends,
+                        self.id.0,
+                        idx,
+                    );
+                    CStr::from_ptr(key).to_string_lossy().into_owned()
+                })
+                .collect()
+        }
+    }
+
+    /// Returns the rich presence keys of the friend that have a special
+    /// meaning to steam
+    pub fn rich_presence_info(&self) -> RichPresence {
+        RichPresence::from_keys(|key| self.rich_presence(key))
+    }
+
     /// Returns information about the game the player is current playing if any
     pub fn game_played(&self) -> Option<FriendGame> {
         unsafe {
@@ -262,73 +820,205 @@ impl<Manager> Friend<Manager> {
         }
     }
 
-    /// Returns a small (32x32) avatar for the user in RGBA format
-    pub fn small_avatar(&self) -> Option<Vec<u8>> {
+    /// Returns a small (32x32) avatar for the user
+    pub fn small_avatar(&self) -> Option<SteamImage> {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
             let img = sys::SteamAPI_ISteamFriends_GetSmallFriendAvatar(self.friends, self.id.0);
-            if img == 0 {
-                return None;
-            }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, img, &mut width, &mut height) {
-                return None;
-            }
-            assert_eq!(width, 32);
-            assert_eq!(height, 32);
-            let mut dest = vec![0; 32 * 32 * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, img, dest.as_mut_ptr(), 32 * 32 * 4) {
-                return None;
-            }
-            Some(dest)
+            load_image(sys::SteamAPI_SteamUtils_v010(), img)
         }
     }
 
-    /// Returns a medium (64x64) avatar for the user in RGBA format
-    pub fn medium_avatar(&self) -> Option<Vec<u8>> {
+    /// Returns a medium (64x64) avatar for the user
+    pub fn medium_avatar(&self) -> Option<SteamImage> {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
             let img = sys::SteamAPI_ISteamFriends_GetMediumFriendAvatar(self.friends, self.id.0);
-            if img == 0 {
-                return None;
-            }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, img, &mut width, &mut height) {
-                return None;
-            }
-            assert_eq!(width, 64);
-            assert_eq!(height, 64);
-            let mut dest = vec![0; 64 * 64 * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, img, dest.as_mut_ptr(), 64 * 64 * 4) {
-                return None;
-            }
-            Some(dest)
+            load_image(sys::SteamAPI_SteamUtils_v010(), img)
         }
     }
 
-    /// Returns a large (184x184) avatar for the user in RGBA format
-    pub fn large_avatar(&self) -> Option<Vec<u8>> {
+    /// Returns a large (184x184) avatar for the user
+    pub fn large_avatar(&self) -> Option<SteamImage> {
         unsafe {
-            let utils = sys::SteamAPI_SteamUtils_v010();
             let img = sys::SteamAPI_ISteamFriends_GetLargeFriendAvatar(self.friends, self.id.0);
-            if img == 0 {
-                return None;
-            }
-            let mut width = 0;
-            let mut height = 0;
-            if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, img, &mut width, &mut height) {
-                return None;
-            }
-            assert_eq!(width, 184);
-            assert_eq!(height, 184);
-            let mut dest = vec![0; 184 * 184 * 4];
-            if !sys::SteamAPI_ISteamUtils_GetImageRGBA(utils, img, dest.as_mut_ptr(), 184 * 184 * 4)
-            {
-                return None;
+            load_image(sys::SteamAPI_SteamUtils_v010(), img)
+        }
+    }
+
+    /// Loads the large (184x184) avatar of the user, passing it to
+    /// the callback once it is available.
+    ///
+    /// The user's information is requested from steam if it isn't
+    /// cached yet. If the avatar is already loaded the callback is
+    /// called before this returns, otherwise it is called by
+    /// `SingleClient::run_callbacks` once the avatar arrives.
+    ///
+    /// The callback is passed `None` if the user has no avatar, once
+    /// their information is known. It is never called after the
+    /// returned handle has been dropped.
+    pub fn load_large_avatar<F>(&self, cb: F) -> AvatarHandle<Manager>
+    where
+        F: FnOnce(Option<SteamImage>) + 'static + Send,
+    {
+        let loader = Arc::new(AvatarLoader {
+            id: self.id,
+            friends: self.friends,
+            pending: Mutex::new(Some(Box::new(cb))),
+        });
+        let id = self.id;
+        let mut handles = unsafe {
+            let on_change = {
+                let loader = loader.clone();
+                // Posted once requested information has been fetched,
+                // even for users without an avatar
+                register_callback(&self.inner, move |v: PersonaStateChange| {
+                    if v.steam_id == id {
+                        loader.try_load(true);
+                    }
+                })
+            };
+            let on_loaded = {
+                let loader = loader.clone();
+                register_callback(&self.inner, move |v: AvatarImageLoaded| {
+                    if v.steam_id == id {
+                        loader.try_load(true);
+                    }
+                })
+            };
+            vec![on_change, on_loaded]
+        };
+        let requested = unsafe {
+            sys::SteamAPI_ISteamFriends_RequestUserInformation(self.friends, id.0, false)
+        };
+        loader.try_load(!requested);
+        if !loader.is_pending() {
+            // Already cached, nothing left to wait for
+            handles.clear();
+        }
+        AvatarHandle {
+            loader,
+            _handles: handles,
+        }
+    }
+
+    /// Async version of [`load_large_avatar`](#method.load_large_avatar)
+    pub fn load_large_avatar_async(&self) -> AvatarFuture<Manager> {
+        let state = Arc::new(Mutex::new(AvatarFutureState {
+            result: None,
+            waker: None,
+        }));
+        let handle = {
+            let state = state.clone();
+            self.load_large_avatar(move |image| {
+                let waker = {
+                    let mut state = state.lock().unwrap();
+                    state.result = Some(image);
+                    state.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            })
+        };
+        AvatarFuture { handle, state }
+    }
+}
+
+type AvatarCallback = Box<dyn FnOnce(Option<SteamImage>) + Send>;
+
+/// The state of a `Friend::load_large_avatar` call, shared with its
+/// callback handlers
+struct AvatarLoader {
+    id: SteamId,
+    friends: *mut sys::ISteamFriends,
+    pending: Mutex<Option<AvatarCallback>>,
+}
+unsafe impl Send for AvatarLoader {}
+unsafe impl Sync for AvatarLoader {}
+
+impl AvatarLoader {
+    /// Passes the large avatar of the user to the pending callback if
+    /// it has been loaded, or `None` if the user's information is
+    /// `known` and they have no avatar.
+    fn try_load(&self, known: bool) {
+        let cb = {
+            let mut pending = self.pending.lock().unwrap();
+            if pending.is_none() {
+                return;
             }
-            Some(dest)
+            let image = unsafe {
+                let img = sys::SteamAPI_ISteamFriends_GetLargeFriendAvatar(self.friends, self.id.0);
+                // 0 means no avatar is set and -1 that it is still loading.
+                // Until the user's information arrives steam reports 0 too.
+                if img == 0 && known {
+                    None
+                } else {
+                    match load_image(sys::SteamAPI_SteamUtils_v010(), img) {
+                        Some(image) => Some(image),
+                        None => return,
+                    }
+                }
+            };
+            pending.take().map(|cb| (cb, image))
+        };
+        if let Some((cb, image)) = cb {
+            cb(image);
+        }
+    }
+
+    fn is_pending(&self) -> bool {
+        self.pending.lock().unwrap().is_some()
+    }
+}
+
+/// A handle to an avatar being loaded by `Friend::load_large_avatar`.
+///
+/// Dropping the handle stops waiting for the avatar.
+pub struct AvatarHandle<Manager = ClientManager> {
+    loader: Arc<AvatarLoader>,
+    _handles: Vec<CallbackHandle<Manager>>,
+}
+
+impl<Manager> AvatarHandle<Manager> {
+    /// Returns whether the avatar hasn't been passed to the callback yet
+    pub fn is_pending(&self) -> bool {
+        self.loader.is_pending()
+    }
+}
+
+/// A future that resolves to the large avatar of a user, or `None` if
+/// they have no avatar.
+///
+/// Created by `Friend::load_large_avatar_async`. Like call results
+/// it only makes progress while `SingleClient::run_callbacks` is
+/// being called.
+#[must_use = "futures do nothing unless polled"]
+pub struct AvatarFuture<Manager = ClientManager> {
+    handle: AvatarHandle<Manager>,
+    state: Arc<Mutex<AvatarFutureState>>,
+}
+
+struct AvatarFutureState {
+    result: Option<Option<SteamImage>>,
+    waker: Option<Waker>,
+}
+
+impl<Manager> AvatarFuture<Manager> {
+    /// Returns the handle of the pending avatar load
+    pub fn handle(&self) -> &AvatarHandle<Manager> {
+        &self.handle
+    }
+}
+
+impl<Manager> Future for AvatarFuture<Manager> {
+    type Output = Option<SteamImage>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<SteamImage>> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(image) = state.result.take() {
+            Poll::Ready(image)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
         }
     }
 }
@@ -342,4 +1032,163 @@ pub enum FriendState {
     Snooze,
     LookingToTrade,
     LookingToPlay,
+    /// Only returned for the current user, who appears offline to others
+    Invisible,
+}
+
+impl FriendState {
+    fn from_raw(state: sys::EPersonaState) -> FriendState {
+        match state {
+            sys::EPersonaState::k_EPersonaStateOnline => FriendState::Online,
+            sys::EPersonaState::k_EPersonaStateBusy => FriendState::Busy,
+            sys::EPersonaState::k_EPersonaStateAway => FriendState::Away,
+            sys::EPersonaState::k_EPersonaStateSnooze => FriendState::Snooze,
+            sys::EPersonaState::k_EPersonaStateLookingToPlay => FriendState::LookingToPlay,
+            sys::EPersonaState::k_EPersonaStateLookingToTrade => FriendState::LookingToTrade,
+            sys::EPersonaState::k_EPersonaStateInvisible => FriendState::Invisible,
+            _ => FriendState::Offline,
+        }
+    }
+}
+
+/// The relationship between the current user and another user
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FriendRelationship {
+    None,
+    Blocked,
+    /// The user sent the current user a friend request
+    RequestRecipient,
+    Friend,
+    /// The current user sent the user a friend request
+    RequestInitiator,
+    Ignored,
+    /// The user is a friend that the current user ignores
+    IgnoredFriend,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::raw::c_char;
+
+    #[test]
+    fn rich_presence_from_keys() {
+        let info = RichPresence::from_keys(|key| match key {
+            RichPresence::DISPLAY => Some("#InMatch".into()),
+            RichPresence::PLAYER_GROUP_SIZE => Some("3".into()),
+            RichPresence::CONNECT => Some("+connect 10.0.0.2:27015".into()),
+            _ => None,
+        });
+        assert_eq!(info.display.as_deref(), Some("#InMatch"));
+        assert_eq!(info.player_group_size, Some(3));
+        assert_eq!(info.player_group, None);
+        assert_eq!(info.status, None);
+        assert_eq!(
+            info.launch_commands().connect.as_deref(),
+            Some("10.0.0.2:27015")
+        );
+
+        // Group sizes are free form strings to steam
+        let info = RichPresence::from_keys(|key| match key {
+            RichPresence::PLAYER_GROUP_SIZE => Some("lots".into()),
+            _ => None,
+        });
+        assert_eq!(info.player_group_size, None);
+        assert!(info.launch_commands().is_empty());
+    }
+
+    #[test]
+    fn game_rich_presence_join_requested_full_connect() {
+        // A full buffer has no terminator
+        let mut raw: sys::GameRichPresenceJoinRequested_t = unsafe { std::mem::zeroed() };
+        raw.m_rgchConnect = [b'a' as c_char; 256];
+        let join =
+            unsafe { GameRichPresenceJoinRequested::from_raw(&mut raw as *mut _ as *mut c_void) };
+        assert_eq!(join.connect, "a".repeat(256));
+    }
+
+    #[test]
+    fn overlay_dialog_names() {
+        // The names steam documents for `ActivateGameOverlay`
+        let dialogs = [
+            (OverlayDialog::Friends, "friends"),
+            (OverlayDialog::Community, "community"),
+            (OverlayDialog::Players, "players"),
+            (OverlayDialog::Settings, "settings"),
+            (OverlayDialog::OfficialGameGroup, "officialgamegroup"),
+            (OverlayDialog::Stats, "stats"),
+            (OverlayDialog::Achievements, "achievements"),
+        ];
+        for (dialog, name) in dialogs {
+            assert_eq!(dialog.as_str(), name);
+        }
+
+        // and for `ActivateGameOverlayToUser`
+        let dialogs = [
+            (OverlayUserDialog::SteamId, "steamid"),
+            (OverlayUserDialog::Chat, "chat"),
+            (OverlayUserDialog::JoinTrade, "jointrade"),
+            (OverlayUserDialog::Stats, "stats"),
+            (OverlayUserDialog::Achievements, "achievements"),
+            (OverlayUserDialog::FriendAdd, "friendadd"),
+            (OverlayUserDialog::FriendRemove, "friendremove"),
+            (
+                OverlayUserDialog::FriendRequestAccept,
+                "friendrequestaccept",
+            ),
+            (
+                OverlayUserDialog::FriendRequestIgnore,
+                "friendrequestignore",
+            ),
+        ];
+        for (dialog, name) in dialogs {
+            assert_eq!(dialog.as_str(), name);
+        }
+    }
+
+    #[test]
+    fn user_restriction_bits() {
+        use sys::EUserRestriction::*;
+        let restrictions = [
+            (UserRestrictions::NONE, k_nUserRestrictionNone),
+            (UserRestrictions::UNKNOWN, k_nUserRestrictionUnknown),
+            (UserRestrictions::ANY_CHAT, k_nUserRestrictionAnyChat),
+            (UserRestrictions::VOICE_CHAT, k_nUserRestrictionVoiceChat),
+            (UserRestrictions::GROUP_CHAT, k_nUserRestrictionGroupChat),
+            (UserRestrictions::RATING, k_nUserRestrictionRating),
+            (
+                UserRestrictions::GAME_INVITES,
+                k_nUserRestrictionGameInvites,
+            ),
+            (UserRestrictions::TRADING, k_nUserRestrictionTrading),
+        ];
+        for (restriction, raw) in restrictions {
+            assert_eq!(restriction.bits(), raw as u32);
+        }
+        // Bits added by newer steam versions are dropped
+        assert_eq!(
+            UserRestrictions::from_bits_truncate(0x0104),
+            UserRestrictions::VOICE_CHAT
+        );
+    }
+
+    #[test]
+    fn friend_state_from_raw() {
+        use sys::EPersonaState::*;
+        let states = [
+            (k_EPersonaStateOffline, FriendState::Offline),
+            (k_EPersonaStateOnline, FriendState::Online),
+            (k_EPersonaStateBusy, FriendState::Busy),
+            (k_EPersonaStateAway, FriendState::Away),
+            (k_EPersonaStateSnooze, FriendState::Snooze),
+            (k_EPersonaStateLookingToTrade, FriendState::LookingToTrade),
+            (k_EPersonaStateLookingToPlay, FriendState::LookingToPlay),
+            (k_EPersonaStateInvisible, FriendState::Invisible),
+            // Not a real state, treated like any unknown one
+            (k_EPersonaStateMax, FriendState::Offline),
+        ];
+        for (raw, state) in states {
+            assert_eq!(FriendState::from_raw(raw), state);
+        }
+    }
 }
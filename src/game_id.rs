@@ -0,0 +1,240 @@
+use super::*;
+
+const APP_ID_MASK: u64 = 0x00FF_FFFF;
+const GAME_TYPE_SHIFT: u64 = 24;
+const GAME_TYPE_MASK: u64 = 0xFF;
+const MOD_ID_SHIFT: u64 = 32;
+/// Set on the mod id of mods, shortcuts and P2P games so they
+/// can't collide with ids of steam apps
+const MOD_ID_HIGH_BIT: u32 = 0x8000_0000;
+
+/// A game id
+///
+/// Combines an `AppId` with the type of the game and, for mods,
+/// shortcuts and P2P games, a mod id.
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameId(pub(crate) u64);
+
+impl GameId {
+    /// Creates a `GameId` from a raw 64 bit value.
+    ///
+    /// May be useful for deserializing game ids from
+    /// a network or save format.
+    pub fn from_raw(id: u64) -> GameId {
+        GameId(id)
+    }
+
+    /// Creates a `GameId` from its parts.
+    ///
+    /// Only the lower 24 bits of the app id are used.
+    pub fn new(app_id: AppId, game_type: GameType, mod_id: u32) -> GameId {
+        GameId(
+            (u64::from(app_id.0) & APP_ID_MASK)
+                | (game_type as u64) << GAME_TYPE_SHIFT
+                | u64::from(mod_id) << MOD_ID_SHIFT,
+        )
+    }
+
+    /// Creates the `GameId` of a steam app
+    pub fn from_app_id(app_id: AppId) -> GameId {
+        GameId::new(app_id, GameType::App, 0)
+    }
+
+    /// Creates the `GameId` of a mod for the given app.
+    ///
+    /// `mod_dir` is the mod's directory, as passed to the game
+    /// with `-game`. The mod id is derived from it the same way
+    /// the steam client does.
+    pub fn game_mod(app_id: AppId, mod_dir: &str) -> GameId {
+        let mod_id = crc32(mod_dir.as_bytes(), 0) | MOD_ID_HIGH_BIT;
+        GameId::new(app_id, GameType::GameMod, mod_id)
+    }
+
+    /// Creates the `GameId` of a non-steam game added to the
+    /// library as a shortcut.
+    ///
+    /// The mod id is derived from the shortcut's executable path and
+    /// name the same way the steam client does.
+    pub fn shortcut(exe: &str, name: &str) -> GameId {
+        let crc = crc32(exe.as_bytes(), 0);
+        let mod_id = crc32(name.as_bytes(), crc) | MOD_ID_HIGH_BIT;
+        GameId::new(AppId(0), GameType::Shortcut, mod_id)
+    }
+
+    /// Creates the `GameId` of a P2P game with the given mod id.
+    pub fn p2p(mod_id: u32) -> GameId {
+        GameId::new(AppId(0), GameType::P2P, mod_id | MOD_ID_HIGH_BIT)
+    }
+
+    /// Returns the raw 64 bit value of the game id
+    ///
+    /// May be useful for serializing game ids over a
+    /// network or to a save format.
+    pub fn raw(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the app id of this game
+    ///
+    /// Shortcuts and P2P games don't have an app id and
+    /// return `AppId(0)`.
+    pub fn app_id(&self) -> AppId {
+        AppId((self.0 & APP_ID_MASK) as u32)
+    }
+
+    /// Returns the type of this game, if known
+    pub fn game_type(&self) -> Option<GameType> {
+        match (self.0 >> GAME_TYPE_SHIFT) & GAME_TYPE_MASK {
+            0 => Some(GameType::App),
+            1 => Some(GameType::GameMod),
+            2 => Some(GameType::Shortcut),
+            3 => Some(GameType::P2P),
+            _ => None,
+        }
+    }
+
+    /// Returns the mod id of this game.
+    ///
+    /// This is zero for steam apps.
+    pub fn mod_id(&self) -> u32 {
+        (self.0 >> MOD_ID_SHIFT) as u32
+    }
+
+    /// Returns whether this is the id of a steam app
+    pub fn is_app(&self) -> bool {
+        self.game_type() == Some(GameType::App)
+    }
+
+    /// Returns whether this is the id of a mod
+    pub fn is_mod(&self) -> bool {
+        self.game_type() == Some(GameType::GameMod)
+    }
+
+    /// Returns whether this is the id of a shortcut
+    pub fn is_shortcut(&self) -> bool {
+        self.game_type() == Some(GameType::Shortcut)
+    }
+
+    /// Returns whether this is the id of a P2P game
+    pub fn is_p2p(&self) -> bool {
+        self.game_type() == Some(GameType::P2P)
+    }
+
+    /// Returns whether the parts of this game id are consistent
+    /// with its type.
+    pub fn is_valid(&self) -> bool {
+        let app_id = self.app_id().0;
+        let high_bit = self.mod_id() & MOD_ID_HIGH_BIT != 0;
+        match self.game_type() {
+            Some(GameType::App) => app_id != 0,
+            Some(GameType::GameMod) => app_id != 0 && high_bit,
+            Some(GameType::Shortcut) => high_bit,
+            Some(GameType::P2P) => app_id == 0 && high_bit,
+            None => false,
+        }
+    }
+}
+
+impl From<AppId> for GameId {
+    fn from(app_id: AppId) -> GameId {
+        GameId::from_app_id(app_id)
+    }
+}
+
+/// The kind of game a `GameId` refers to
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameType {
+    /// A steam app
+    App = 0,
+    /// A mod of a steam app, e.g. a source engine mod
+    GameMod = 1,
+    /// A non-steam game added to the library
+    Shortcut = 2,
+    /// A P2P game
+    P2P = 3,
+}
+
+/// The CRC-32 (IEEE) the steam client uses to derive mod ids
+fn crc32(data: &[u8], crc: u32) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app() {
+        let id = GameId::from_app_id(AppId(480));
+        assert_eq!(id.raw(), 480);
+        assert_eq!(id.app_id(), AppId(480));
+        assert_eq!(id.game_type(), Some(GameType::App));
+        assert_eq!(id.mod_id(), 0);
+        assert!(id.is_app() && id.is_valid());
+        assert_eq!(GameId::from(AppId(480)), id);
+        assert!(!GameId::from_app_id(AppId(0)).is_valid());
+    }
+
+    #[test]
+    fn game_mod() {
+        let id = GameId::game_mod(AppId(240), "cstrike");
+        assert_eq!(id.app_id(), AppId(240));
+        assert!(id.is_mod() && id.is_valid());
+        assert_eq!(id.mod_id(), crc32(b"cstrike", 0) | MOD_ID_HIGH_BIT);
+        assert!(!GameId::new(AppId(240), GameType::GameMod, 1).is_valid());
+    }
+
+    #[test]
+    fn shortcut() {
+        let id = GameId::shortcut("\"C:\\Games\\game.exe\"", "Game");
+        assert_eq!(id.app_id(), AppId(0));
+        assert!(id.is_shortcut() && id.is_valid());
+        assert_eq!(
+            id.mod_id(),
+            crc32(b"\"C:\\Games\\game.exe\"Game", 0) | MOD_ID_HIGH_BIT
+        );
+    }
+
+    #[test]
+    fn p2p() {
+        let id = GameId::p2p(7);
+        assert!(id.is_p2p() && id.is_valid());
+        assert_eq!(id.mod_id(), 7 | MOD_ID_HIGH_BIT);
+        assert!(!GameId::new(AppId(480), GameType::P2P, MOD_ID_HIGH_BIT).is_valid());
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        for id in [
+            GameId::from_app_id(AppId(480)),
+            GameId::game_mod(AppId(240), "cstrike"),
+            GameId::shortcut("game.exe", "Game"),
+            GameId::p2p(7),
+        ] {
+            let raw = GameId::from_raw(id.raw());
+            assert_eq!(raw, id);
+            assert_eq!(
+                GameId::new(raw.app_id(), raw.game_type().unwrap(), raw.mod_id()),
+                id
+            );
+        }
+        let unknown = GameId::from_raw(0x0400_0000 | 480);
+        assert_eq!(unknown.game_type(), None);
+        assert!(!unknown.is_valid());
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32(b"123456789", 0), 0xCBF4_3926);
+        assert_eq!(crc32(b"6789", crc32(b"12345", 0)), 0xCBF4_3926);
+    }
+}
@@ -4,10 +4,16 @@ extern crate thiserror;
 extern crate bitflags;
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+mod trace;
 
-#[cfg(feature = "raw-bindings")]
+#[cfg(all(feature = "raw-bindings", feature = "fake"))]
+pub use crate::fake::sys;
+#[cfg(all(not(feature = "raw-bindings"), feature = "fake"))]
+use crate::fake::sys;
+#[cfg(all(feature = "raw-bindings", not(feature = "fake")))]
 pub use steamworks_sys as sys;
-#[cfg(not(feature = "raw-bindings"))]
+#[cfg(all(not(feature = "raw-bindings"), not(feature = "fake")))]
 use steamworks_sys as sys;
 
 use core::ffi::c_void;
@@ -25,20 +31,27 @@ pub use crate::app::*;
 pub use crate::callback::*;
 pub use crate::error::*;
 pub use crate::friends::*;
+pub use crate::game_id::*;
 pub use crate::input::*;
 pub use crate::matchmaking::*;
 pub use crate::networking::*;
 pub use crate::remote_storage::*;
 pub use crate::server::*;
+pub use crate::steam_id::*;
 pub use crate::ugc::*;
 pub use crate::user::*;
 pub use crate::user_stats::*;
 pub use crate::utils::*;
+#[cfg(feature = "dynamic")]
+pub use steamworks_sys::LoadError;
 
 mod app;
 mod callback;
 mod error;
+#[cfg(feature = "fake")]
+pub mod fake;
 mod friends;
+mod game_id;
 mod input;
 mod matchmaking;
 mod networking;
@@ -49,6 +62,7 @@ pub mod networking_types;
 pub mod networking_utils;
 mod remote_storage;
 mod server;
+mod steam_id;
 mod ugc;
 mod user;
 mod user_stats;
@@ -91,9 +105,23 @@ struct Inner<Manager> {
     networking_sockets_data: Mutex<NetworkingSocketsData<Manager>>,
 }
 
+type CallbackFn = Box<dyn FnMut(*mut c_void) + Send + 'static>;
+type CallResultFn = Box<dyn FnOnce(Result<*mut c_void, SteamError>) + Send + 'static>;
+
+struct PendingCallResult {
+    callback: CallResultFn,
+    /// When to give up on the result, set through its `CallResultHandle`
+    deadline: Option<std::time::Instant>,
+}
+
 struct Callbacks {
-    callbacks: HashMap<i32, Box<dyn FnMut(*mut c_void) + Send + 'static>>,
-    call_results: HashMap<sys::SteamAPICall_t, Box<dyn FnOnce(*mut c_void, bool) + Send + 'static>>,
+    /// Every registered handler for a callback id, in registration order.
+    ///
+    /// Each handler is tagged with the subscription id owned by its
+    /// `CallbackHandle` so that dropping a handle only removes itself.
+    callbacks: HashMap<i32, Vec<Arc<callback::Handler>>>,
+    next_subscription: u64,
+    call_results: HashMap<sys::SteamAPICall_t, PendingCallResult>,
 }
 
 struct NetworkingSocketsData<Manager> {
@@ -121,9 +149,105 @@ unsafe impl<Manager: Send + Sync> Send for SingleClient<Manager> {}
 /// Returns false if the app was either launched through steam
 /// or has a `steam_appid.txt`
 pub fn restart_app_if_necessary(app_id: AppId) -> bool {
+    if ensure_steam_api("SteamAPI_RestartAppIfNecessary").is_err() {
+        return false;
+    }
     unsafe { sys::SteamAPI_RestartAppIfNecessary(app_id.0) }
 }
 
+/// Loads the steam api library from the given path.
+///
+/// Only available with the `dynamic` feature. This has to be called before
+/// `Client::init` or `Server::init` to use a library outside of the default
+/// search paths, otherwise they try to load the library by its file name.
+/// Only one library can be loaded per process.
+#[cfg(feature = "dynamic")]
+pub fn load_steam_api<P: AsRef<std::ffi::OsStr>>(path: P) -> Result<(), LoadError> {
+    steamworks_sys::load_library(path)
+}
+
+/// Loads the steam api library if it isn't yet and checks that the
+/// given entry point exists, so that a missing library is an error
+/// instead of a panic on the first call.
+#[allow(unused_variables)]
+fn ensure_steam_api(entry_point: &str) -> Result<(), InitError> {
+    #[cfg(all(feature = "dynamic", not(feature = "fake")))]
+    {
+        if !steamworks_sys::is_library_loaded() {
+            steamworks_sys::load_library(steamworks_sys::LIBRARY_NAME).map_err(|err| {
+                InitError::new(InitErrorReason::LibraryNotLoaded, err.to_string())
+            })?;
+        }
+        if !steamworks_sys::has_symbol(entry_point) {
+            return Err(InitError::new(
+                InitErrorReason::LibraryNotLoaded,
+                format!("the steam api library doesn't export {}", entry_point),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether steam has a way to find out the app ID of the game
+/// without being launched by it
+fn has_app_id() -> bool {
+    std::env::var_os("SteamAppId").is_some() || std::path::Path::new("steam_appid.txt").exists()
+}
+
+fn no_app_id_error() -> InitError {
+    InitError::new(
+        InitErrorReason::NoAppId,
+        "the app ID couldn't be determined, launch the game through steam or \
+         place a steam_appid.txt with the ID in the working directory",
+    )
+}
+
+/// Reads a string out of a fixed size buffer filled in by steam.
+///
+/// Stops at the first nul or at the end of the buffer, so a buffer
+/// without a terminator isn't read past its end.
+pub(crate) fn string_from_buffer(buffer: &[std::os::raw::c_char]) -> String {
+    let bytes: Vec<u8> = buffer
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Calls `fill` with growing buffers until it reports writing fewer
+/// elements than fit, for steam functions that truncate their output
+/// to the buffer without saying how much was left out.
+///
+/// `fill` returns the number of elements written, the result is
+/// truncated to it.
+pub(crate) fn fill_growing<T, F>(initial: usize, mut fill: F) -> Vec<T>
+where
+    T: Copy + Default,
+    F: FnMut(&mut [T]) -> usize,
+{
+    // Guards against a count that never drops below the buffer size
+    const MAX_LEN: usize = 1 << 20;
+    let mut buffer = vec![T::default(); initial.max(1)];
+    loop {
+        let count = fill(&mut buffer);
+        if count < buffer.len() || buffer.len() >= MAX_LEN {
+            buffer.truncate(count);
+            return buffer;
+        }
+        buffer = vec![T::default(); buffer.len() * 2];
+    }
+}
+
+/// Routes the warnings of the steam api and the debug output of
+/// the networking sockets to `tracing`
+#[cfg(feature = "tracing")]
+unsafe fn install_tracing_hooks(utils: *mut sys::ISteamUtils) {
+    sys::SteamAPI_ISteamUtils_SetWarningMessageHook(utils, Some(utils::c_warning_callback));
+    #[cfg(not(feature = "fake"))]
+    networking_utils::install_debug_output();
+}
+
 fn static_assert_send<T: Send>() {}
 fn static_assert_sync<T>()
 where
@@ -150,21 +274,37 @@ impl Client<ClientManager> {
     /// * The game isn't running on the same user/level as the steam client
     /// * The user doesn't own a license for the game.
     /// * The app ID isn't completely set up.
-    pub fn init() -> SResult<(Client<ClientManager>, SingleClient<ClientManager>)> {
+    ///
+    /// The returned [`InitError`] carries which of these was detected
+    /// along with a message that can be shown to the user.
+    pub fn init() -> Result<(Client<ClientManager>, SingleClient<ClientManager>), InitError> {
         static_assert_send::<Client<ClientManager>>();
         static_assert_sync::<Client<ClientManager>>();
         static_assert_send::<SingleClient<ClientManager>>();
+        ensure_steam_api("SteamAPI_Init")?;
         unsafe {
             if !sys::SteamAPI_Init() {
-                return Err(SteamError::InitFailed);
+                return Err(if !sys::SteamAPI_IsSteamRunning() {
+                    InitError::new(
+                        InitErrorReason::NoSteamClient,
+                        "the steam client isn't running",
+                    )
+                } else if !has_app_id() {
+                    no_app_id_error()
+                } else {
+                    InitError::new(
+                        InitErrorReason::Generic,
+                        "steam refused to start the game, make sure you own it and \
+                         steam is running as the same user",
+                    )
+                });
             }
             sys::SteamAPI_ManualDispatch_Init();
+            #[cfg(feature = "tracing")]
+            install_tracing_hooks(sys::SteamAPI_SteamUtils_v010());
             let client = Arc::new(Inner {
                 _manager: ClientManager { _priv: () },
-                callbacks: Mutex::new(Callbacks {
-                    callbacks: HashMap::new(),
-                    call_results: HashMap::new(),
-                }),
+                callbacks: Mutex::new(Callbacks::new()),
                 networking_sockets_data: Mutex::new(NetworkingSocketsData {
                     sockets: Default::default(),
                     independent_connections: Default::default(),
@@ -197,7 +337,7 @@ impl Client<ClientManager> {
     /// * The app ID isn't completely set up.
     pub fn init_app<ID: Into<AppId>>(
         app_id: ID,
-    ) -> SResult<(Client<ClientManager>, SingleClient<ClientManager>)> {
+    ) -> Result<(Client<ClientManager>, SingleClient<ClientManager>), InitError> {
         let app_id = app_id.into().0.to_string();
         std::env::set_var("SteamAppId", &app_id);
         std::env::set_var("SteamGameId", app_id);
@@ -215,40 +355,136 @@ where
     ///
     /// This should be called frequently (e.g. once per a frame)
     /// in order to reduce the latency between recieving events.
+    ///
+    /// Pending call results whose deadline has passed are timed
+    /// out afterwards.
+    ///
+    /// If a handler panics the remaining callbacks are still run and
+    /// the first panic is resumed once they are done, any further
+    /// panics are reported. Use `try_run_callbacks` to handle the
+    /// panics instead.
     pub fn run_callbacks(&self) {
+        let mut panics = self.try_run_callbacks().into_iter();
+        if let Some(panic) = panics.next() {
+            for other in panics {
+                report_error!(
+                    "callback handler panicked: {}",
+                    other.message().unwrap_or("unknown panic")
+                );
+            }
+            panic.resume();
+        }
+    }
+
+    /// Runs any currently pending callbacks, returning the panics
+    /// of any handlers instead of resuming them.
+    ///
+    /// The callback registry stays usable after a handler panicked.
+    #[must_use]
+    pub fn try_run_callbacks(&self) -> Vec<CallbackPanic> {
+        let mut panics = Vec::new();
         unsafe {
             let pipe = M::get_pipe();
             sys::SteamAPI_ManualDispatch_RunFrame(pipe);
             let mut callback = std::mem::zeroed();
             while sys::SteamAPI_ManualDispatch_GetNextCallback(pipe, &mut callback) {
-                let mut callbacks = self.inner.callbacks.lock().unwrap();
                 if callback.m_iCallback == sys::SteamAPICallCompleted_t_k_iCallback as i32 {
                     let apicall =
                         &mut *(callback.m_pubParam as *mut _ as *mut sys::SteamAPICallCompleted_t);
+                    // Copied out to avoid taking references to the packed fields
+                    let api_call = apicall.m_hAsyncCall;
+                    let callback_id = apicall.m_iCallback;
                     let mut apicall_result = vec![0; apicall.m_cubParam as usize];
                     let mut failed = false;
                     if sys::SteamAPI_ManualDispatch_GetAPICallResult(
                         pipe,
-                        apicall.m_hAsyncCall,
+                        api_call,
                         apicall_result.as_mut_ptr() as *mut _,
                         apicall.m_cubParam as _,
-                        apicall.m_iCallback,
+                        callback_id,
                         &mut failed,
                     ) {
-                        // The &{val} pattern here is to avoid taking a reference to a packed field
-                        // Since the value here is Copy, we can just copy it and borrow the copy
-                        if let Some(cb) = callbacks.call_results.remove(&{ apicall.m_hAsyncCall }) {
-                            cb(apicall_result.as_mut_ptr() as *mut _, failed);
+                        let cb = self
+                            .inner
+                            .callbacks
+                            .lock()
+                            .unwrap()
+                            .call_results
+                            .remove(&api_call);
+                        if let Some(cb) = cb {
+                            event!(
+                                debug,
+                                api_call,
+                                callback_id,
+                                failed,
+                                "call result completed"
+                            );
+                            let result = if failed {
+                                Err(SteamError::IOFailure)
+                            } else {
+                                Ok(apicall_result.as_mut_ptr() as *mut _)
+                            };
+                            if let Err(panic) =
+                                CallbackPanic::catch(Some(callback_id), || (cb.callback)(result))
+                            {
+                                panics.push(panic);
+                            }
+                        } else {
+                            event!(
+                                trace,
+                                api_call,
+                                callback_id,
+                                "call result without a handler"
+                            );
                         }
+                    } else {
+                        event!(warn, api_call, callback_id, "failed to get the call result");
                     }
                 } else {
-                    if let Some(cb) = callbacks.callbacks.get_mut(&callback.m_iCallback) {
-                        cb(callback.m_pubParam as *mut _);
-                    }
+                    event!(
+                        trace,
+                        callback_id = callback.m_iCallback,
+                        "dispatching callback"
+                    );
+                    panics.extend(dispatch(
+                        &self.inner,
+                        callback.m_iCallback,
+                        callback.m_pubParam as *mut _,
+                    ));
                 }
                 sys::SteamAPI_ManualDispatch_FreeLastCallback(pipe);
             }
         }
+        let expired = self
+            .inner
+            .callbacks
+            .lock()
+            .unwrap()
+            .expired_call_results(std::time::Instant::now());
+        for (_api_call, cb) in expired {
+            event!(debug, api_call = _api_call, "call result timed out");
+            if let Err(panic) = CallbackPanic::catch(None, || cb(Err(SteamError::Timeout))) {
+                panics.push(panic);
+            }
+        }
+        panics.extend(utils::take_warning_panics());
+        panics
+    }
+}
+
+impl<M> SingleClient<M>
+where
+    M: Manager + Send + Sync + 'static,
+{
+    /// Moves the client onto a background thread that calls
+    /// `run_callbacks` every `interval`.
+    ///
+    /// This is meant for programs without a frame loop, such as
+    /// dedicated servers. Callbacks and call results then run on
+    /// that thread. The thread stops when the returned pump is
+    /// dropped.
+    pub fn spawn_pump(self, interval: std::time::Duration) -> CallbackPump<M> {
+        CallbackPump::spawn(self, interval)
     }
 }
 
@@ -266,6 +502,29 @@ impl<Manager> Client<Manager> {
         unsafe { register_callback(&self.inner, f) }
     }
 
+    /// Returns a receiver that queues every callback of the given type.
+    ///
+    /// The callbacks are sent when `run_callbacks` is called and can be
+    /// drained from the receiver at any point afterwards. The subscription
+    /// is removed once the receiver is dropped.
+    pub fn subscribe<C>(&self) -> CallbackReceiver<C, Manager>
+    where
+        C: Callback + Send + 'static,
+    {
+        unsafe { subscribe(&self.inner) }
+    }
+
+    /// Returns a `Stream` of every callback of the given type.
+    ///
+    /// The stream is woken when `run_callbacks` receives a new callback.
+    /// The subscription is removed once the stream is dropped.
+    pub fn subscribe_stream<C>(&self) -> CallbackStream<C, Manager>
+    where
+        C: Callback + Send + 'static,
+    {
+        unsafe { subscribe_stream(&self.inner) }
+    }
+
     /// Returns an accessor to the steam utils interface
     pub fn utils(&self) -> Utils<Manager> {
         unsafe {
@@ -309,7 +568,7 @@ impl<Manager> Client<Manager> {
             debug_assert!(!apps.is_null());
             Apps {
                 apps: apps,
-                _inner: self.inner.clone(),
+                inner: self.inner.clone(),
             }
         }
     }
@@ -447,100 +706,6 @@ impl Drop for ClientManager {
     }
 }
 
-/// A user's steam id
-#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct SteamId(pub(crate) u64);
-
-impl SteamId {
-    /// Creates a `SteamId` from a raw 64 bit value.
-    ///
-    /// May be useful for deserializing steam ids from
-    /// a network or save format.
-    pub fn from_raw(id: u64) -> SteamId {
-        SteamId(id)
-    }
-
-    /// Returns the raw 64 bit value of the steam id
-    ///
-    /// May be useful for serializing steam ids over a
-    /// network or to a save format.
-    pub fn raw(&self) -> u64 {
-        self.0
-    }
-
-    /// Returns the account id for this steam id
-    pub fn account_id(&self) -> AccountId {
-        unsafe {
-            let bits = sys::CSteamID_SteamID_t {
-                m_unAll64Bits: self.0,
-            };
-            AccountId(bits.m_comp.m_unAccountID())
-        }
-    }
-
-    /// Returns the formatted SteamID32 string for this steam id.
-    pub fn steamid32(&self) -> String {
-        let account_id = self.account_id().raw();
-        let last_bit = account_id & 1;
-        format!("STEAM_0:{}:{}", last_bit, (account_id >> 1))
-    }
-}
-
-/// A user's account id
-#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct AccountId(pub(crate) u32);
-
-impl AccountId {
-    /// Creates an `AccountId` from a raw 32 bit value.
-    ///
-    /// May be useful for deserializing account ids from
-    /// a network or save format.
-    pub fn from_raw(id: u32) -> AccountId {
-        AccountId(id)
-    }
-
-    /// Returns the raw 32 bit value of the steam id
-    ///
-    /// May be useful for serializing steam ids over a
-    /// network or to a save format.
-    pub fn raw(&self) -> u32 {
-        self.0
-    }
-}
-
-/// A game id
-///
-/// Combines `AppId` and other information
-#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct GameId(pub(crate) u64);
-
-impl GameId {
-    /// Creates a `GameId` from a raw 64 bit value.
-    ///
-    /// May be useful for deserializing game ids from
-    /// a network or save format.
-    pub fn from_raw(id: u64) -> GameId {
-        GameId(id)
-    }
-
-    /// Returns the raw 64 bit value of the game id
-    ///
-    /// May be useful for serializing game ids over a
-    /// network or to a save format.
-    pub fn raw(&self) -> u64 {
-        self.0
-    }
-
-    /// Returns the app id of this game
-    pub fn app_id(&self) -> AppId {
-        // TODO: Relies on internal details
-        AppId((self.0 & 0xFF_FF_FF) as u32)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use serial_test_derive::serial;
@@ -587,6 +752,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn string_from_buffers() {
+        let buffer = [b'a' as _, b'b' as _, 0, b'c' as _];
+        assert_eq!(string_from_buffer(&buffer), "ab");
+        // Filled without a terminator
+        assert_eq!(string_from_buffer(&[b'x' as _; 3]), "xxx");
+        assert_eq!(string_from_buffer(&[0; 4]), "");
+        assert_eq!(string_from_buffer(&[0xff_u8 as _, 0]), "\u{fffd}");
+    }
+
+    #[test]
+    fn fill_growing_buffers() {
+        let items: Vec<u32> = (0..100).collect();
+        let mut sizes = Vec::new();
+        let filled = fill_growing(16, |buffer: &mut [u32]| {
+            sizes.push(buffer.len());
+            let count = buffer.len().min(items.len());
+            buffer[..count].copy_from_slice(&items[..count]);
+            count
+        });
+        assert_eq!(filled, items);
+        assert_eq!(sizes, vec![16, 32, 64, 128]);
+
+        // An exact fit needs one more call to be sure nothing is left
+        let filled = fill_growing(4, |buffer: &mut [u32]| buffer.len().min(4));
+        assert_eq!(filled.len(), 4);
+        assert!(fill_growing(4, |_: &mut [u32]| 0).is_empty());
+    }
+
     #[test]
     fn steamid_test() {
         let steamid = SteamId(76561198040894045);
@@ -43,7 +43,19 @@ impl LobbyId {
 }
 
 impl<Manager> Matchmaking<Manager> {
-    pub fn request_lobby_list<F>(&self, cb: F)
+    pub fn request_lobby_list<F>(&self, cb: F) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(SResult<Vec<LobbyId>>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.request_lobby_list_call(cb))
+    }
+
+    /// Async version of [`request_lobby_list`](#method.request_lobby_list)
+    pub fn request_lobby_list_async(&self) -> CallResultFuture<SResult<Vec<LobbyId>>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| self.request_lobby_list_call(cb))
+    }
+
+    fn request_lobby_list_call<F>(&self, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(SResult<Vec<LobbyId>>) + 'static + Send,
     {
@@ -53,21 +65,23 @@ impl<Manager> Matchmaking<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 10,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else {
-                        let mut out = Vec::with_capacity(v.m_nLobbiesMatching as usize);
-                        for idx in 0..v.m_nLobbiesMatching {
-                            out.push(LobbyId(sys::SteamAPI_ISteamMatchmaking_GetLobbyByIndex(
-                                sys::SteamAPI_SteamMatchmaking_v009(),
-                                idx as _,
-                            )));
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) => {
+                            let mut out = Vec::with_capacity(v.m_nLobbiesMatching as usize);
+                            for idx in 0..v.m_nLobbiesMatching {
+                                out.push(LobbyId(sys::SteamAPI_ISteamMatchmaking_GetLobbyByIndex(
+                                    sys::SteamAPI_SteamMatchmaking_v009(),
+                                    idx as _,
+                                )));
+                            }
+                            Ok(out)
                         }
-                        Ok(out)
                     })
                 },
             );
+            api_call
         }
     }
 
@@ -81,7 +95,30 @@ impl<Manager> Matchmaking<Manager> {
     ///
     /// * `LobbyEnter`
     /// * `LobbyCreated`
-    pub fn create_lobby<F>(&self, ty: LobbyType, max_members: u32, cb: F)
+    pub fn create_lobby<F>(
+        &self,
+        ty: LobbyType,
+        max_members: u32,
+        cb: F,
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(SResult<LobbyId>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.create_lobby_call(ty, max_members, cb))
+    }
+
+    /// Async version of [`create_lobby`](#method.create_lobby)
+    pub fn create_lobby_async(
+        &self,
+        ty: LobbyType,
+        max_members: u32,
+    ) -> CallResultFuture<SResult<LobbyId>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.create_lobby_call(ty, max_members, cb)
+        })
+    }
+
+    fn create_lobby_call<F>(&self, ty: LobbyType, max_members: u32, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(SResult<LobbyId>) + 'static + Send,
     {
@@ -99,21 +136,37 @@ impl<Manager> Matchmaking<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 13,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        Err(v.m_eResult.into())
-                    } else {
-                        Ok(LobbyId(v.m_ulSteamIDLobby))
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if v.m_eResult != sys::EResult::k_EResultOK => {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(v) => Ok(LobbyId(v.m_ulSteamIDLobby)),
                     })
                 },
             );
+            api_call
         }
     }
 
     /// Tries to join the lobby with the given ID
-    pub fn join_lobby<F>(&self, lobby: LobbyId, cb: F)
+    pub fn join_lobby<F>(&self, lobby: LobbyId, cb: F) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<LobbyId, ()>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.join_lobby_call(lobby, cb))
+    }
+
+    /// Async version of [`join_lobby`](#method.join_lobby)
+    pub fn join_lobby_async(
+        &self,
+        lobby: LobbyId,
+    ) -> CallResultFuture<Result<LobbyId, ()>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| self.join_lobby_call(lobby, cb))
+    }
+
+    fn join_lobby_call<F>(&self, lobby: LobbyId, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<LobbyId, ()>) + 'static + Send,
     {
@@ -123,16 +176,15 @@ impl<Manager> Matchmaking<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 4,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(())
-                    } else if v.m_EChatRoomEnterResponse != 1 {
-                        Err(())
-                    } else {
-                        Ok(LobbyId(v.m_ulSteamIDLobby))
+                move |v| {
+                    cb(match v {
+                        Err(_) => Err(()),
+                        Ok(v) if v.m_EChatRoomEnterResponse != 1 => Err(()),
+                        Ok(v) => Ok(LobbyId(v.m_ulSteamIDLobby)),
                     })
                 },
             );
+            api_call
         }
     }
 
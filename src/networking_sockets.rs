@@ -5,7 +5,7 @@ use crate::networking_types::{
     SendFlags, SteamIpAddr,
 };
 use crate::{CallbackHandle, Inner, SResult};
-#[cfg(test)]
+#[cfg(all(test, not(feature = "fake")))]
 use serial_test_derive::serial;
 use std::convert::TryInto;
 use std::ffi::CString;
@@ -13,7 +13,7 @@ use std::net::SocketAddr;
 use std::sync::mpsc::Receiver;
 use std::sync::Arc;
 
-use steamworks_sys as sys;
+use crate::sys;
 
 /// Access to the steam networking sockets interface
 pub struct NetworkingSockets<Manager> {
@@ -411,7 +411,7 @@ impl<Manager> Drop for InnerSocket<Manager> {
             .sockets
             .remove(&self.handle)
         {
-            eprintln!("error while dropping InnerSocket: socket was already removed")
+            report_error!("error while dropping InnerSocket: socket was already removed")
         }
     }
 }
@@ -877,7 +877,8 @@ impl<Manager> Drop for NetPollGroup<Manager> {
 #[error("operation was unsuccessful an invalid handle was returned")]
 pub struct InvalidHandle;
 
-#[cfg(test)]
+// The fake backend doesn't simulate networking
+#[cfg(all(test, not(feature = "fake")))]
 mod tests {
     use std::net::Ipv4Addr;
 
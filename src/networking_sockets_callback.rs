@@ -2,14 +2,17 @@ use crate::networking_sockets::NetConnection;
 use crate::networking_types::{
     NetConnectionEnd, NetConnectionStatusChanged, NetworkingConnectionState,
 };
+use crate::sys;
 use crate::{register_callback, CallbackHandle, Inner};
 use std::sync::{Arc, Weak};
-use steamworks_sys as sys;
 use sys::ISteamNetworkingSockets;
 
 /// All independent connections (to a remote host) and listening sockets share the same Callback for
 /// `NetConnectionStatusChangedCallback`. This function either returns the existing handle, or creates a new
 /// handler.
+///
+/// The handler is a regular subscription in the callback registry, so user code can still register its own
+/// `NetConnectionStatusChanged` callbacks alongside it.
 pub(crate) fn get_or_create_connection_callback<Manager: 'static>(
     inner: Arc<Inner<Manager>>,
     sockets: *mut ISteamNetworkingSockets,
@@ -44,6 +47,14 @@ unsafe impl<Manager> Sync for ConnectionCallbackHandler<Manager> {}
 
 impl<Manager: 'static> ConnectionCallbackHandler<Manager> {
     pub(crate) fn callback(&self, event: NetConnectionStatusChanged) {
+        event!(
+            debug,
+            connection = event.connection,
+            old_state = ?event.old_state,
+            state = ?event.connection_info.state(),
+            end_reason = ?event.connection_info.end_reason(),
+            "connection status changed"
+        );
         if let Some(socket) = event.connection_info.listen_socket() {
             self.listen_socket_callback(socket, event);
         } else {
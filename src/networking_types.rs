@@ -1899,6 +1899,7 @@ impl From<sys::SteamNetworkingIPAddr> for SteamIpAddr {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(not(feature = "fake"))]
     use crate::Client;
     use std::net::Ipv4Addr;
 
@@ -1930,6 +1931,8 @@ mod tests {
         assert_eq!("ip:192.168.0.5:1234", &id.debug_string())
     }
 
+    // The fake backend doesn't simulate networking
+    #[cfg(not(feature = "fake"))]
     #[test]
     fn test_allocate_and_free_message() {
         let (client, _single) = Client::init().unwrap();
@@ -2,6 +2,8 @@ use crate::networking_types::{NetworkingAvailabilityResult, NetworkingMessage};
 use crate::{register_callback, Callback, Inner};
 use std::convert::TryInto;
 use std::ffi::{c_void, CStr};
+#[cfg(all(feature = "tracing", not(feature = "fake")))]
+use std::os::raw::c_char;
 use std::sync::Arc;
 
 use steamworks_sys as sys;
@@ -16,6 +18,20 @@ unsafe impl<T> Send for NetworkingUtils<T> {}
 unsafe impl<T> Sync for NetworkingUtils<T> {}
 
 impl<Manager> NetworkingUtils<Manager> {
+    /// Routes the debug output of the networking sockets to `tracing`.
+    ///
+    /// `Client::init` does this already, but steam only sends output up
+    /// to the most verbose level the subscribers were interested in at
+    /// that point. Call this again after installing a subscriber or
+    /// changing its filter later on.
+    #[cfg(feature = "tracing")]
+    pub fn install_debug_output(&self) {
+        #[cfg(not(feature = "fake"))]
+        unsafe {
+            install_debug_output();
+        }
+    }
+
     /// Allocate and initialize a message object.  Usually the reason
     /// you call this is to pass it to ISteamNetworkingSockets::SendMessages.
     /// The returned object will have all of the relevant fields cleared to zero.
@@ -193,7 +209,64 @@ unsafe impl Callback for RelayNetworkStatusCallback {
     }
 }
 
-#[cfg(test)]
+/// Routes the debug output of the networking sockets to `tracing`.
+///
+/// The detail level follows the most verbose level any subscriber
+/// is interested in at the time this is called, later changes need
+/// another call.
+#[cfg(all(feature = "tracing", not(feature = "fake")))]
+pub(crate) unsafe fn install_debug_output() {
+    use sys::ESteamNetworkingSocketsDebugOutputType as Output;
+    use tracing::level_filters::LevelFilter;
+
+    let level = match LevelFilter::current() {
+        LevelFilter::OFF => return,
+        LevelFilter::ERROR => Output::k_ESteamNetworkingSocketsDebugOutputType_Error,
+        LevelFilter::WARN => Output::k_ESteamNetworkingSocketsDebugOutputType_Warning,
+        LevelFilter::INFO => Output::k_ESteamNetworkingSocketsDebugOutputType_Msg,
+        LevelFilter::DEBUG => Output::k_ESteamNetworkingSocketsDebugOutputType_Debug,
+        LevelFilter::TRACE => Output::k_ESteamNetworkingSocketsDebugOutputType_Everything,
+    };
+    let utils = sys::SteamAPI_SteamNetworkingUtils_SteamAPI_v004();
+    if !utils.is_null() {
+        sys::SteamAPI_ISteamNetworkingUtils_SetDebugOutputFunction(
+            utils,
+            level,
+            Some(c_debug_output),
+        );
+    }
+}
+
+#[cfg(all(feature = "tracing", not(feature = "fake")))]
+unsafe extern "C" fn c_debug_output(
+    ty: sys::ESteamNetworkingSocketsDebugOutputType,
+    msg: *const c_char,
+) {
+    use sys::ESteamNetworkingSocketsDebugOutputType as Output;
+
+    let msg = CStr::from_ptr(msg).to_string_lossy();
+    match ty {
+        Output::k_ESteamNetworkingSocketsDebugOutputType_Bug
+        | Output::k_ESteamNetworkingSocketsDebugOutputType_Error => {
+            tracing::error!(target: "steamworks::networking", "{}", msg)
+        }
+        Output::k_ESteamNetworkingSocketsDebugOutputType_Important
+        | Output::k_ESteamNetworkingSocketsDebugOutputType_Warning => {
+            tracing::warn!(target: "steamworks::networking", "{}", msg)
+        }
+        Output::k_ESteamNetworkingSocketsDebugOutputType_Msg => {
+            tracing::info!(target: "steamworks::networking", "{}", msg)
+        }
+        Output::k_ESteamNetworkingSocketsDebugOutputType_Verbose
+        | Output::k_ESteamNetworkingSocketsDebugOutputType_Debug => {
+            tracing::debug!(target: "steamworks::networking", "{}", msg)
+        }
+        _ => tracing::trace!(target: "steamworks::networking", "{}", msg),
+    }
+}
+
+// The fake backend doesn't simulate networking
+#[cfg(all(test, not(feature = "fake")))]
 mod tests {
     use crate::Client;
     use std::time::Duration;
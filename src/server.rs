@@ -1,5 +1,5 @@
 use super::*;
-#[cfg(test)]
+#[cfg(all(test, not(feature = "fake")))]
 use serial_test_derive::serial;
 use std::net::Ipv4Addr;
 
@@ -61,7 +61,8 @@ impl Server {
         query_port: u16,
         server_mode: ServerMode,
         version: &str,
-    ) -> SResult<(Server, SingleClient<ServerManager>)> {
+    ) -> Result<(Server, SingleClient<ServerManager>), InitError> {
+        crate::ensure_steam_api("SteamInternal_GameServer_Init")?;
         unsafe {
             let version = CString::new(version).unwrap();
             let raw_ip: u32 = ip.into();
@@ -80,16 +81,23 @@ impl Server {
                 server_mode,
                 version.as_ptr(),
             ) {
-                return Err(SteamError::InitFailed);
+                return Err(if !crate::has_app_id() {
+                    crate::no_app_id_error()
+                } else {
+                    InitError::new(
+                        InitErrorReason::Generic,
+                        "steam refused to start the server, make sure the ports are free \
+                         and the app ID is set up for dedicated servers",
+                    )
+                });
             }
             sys::SteamAPI_ManualDispatch_Init();
+            #[cfg(feature = "tracing")]
+            crate::install_tracing_hooks(sys::SteamAPI_SteamGameServerUtils_v010());
             let server_raw = sys::SteamAPI_SteamGameServer_v014();
             let server = Arc::new(Inner {
                 _manager: ServerManager { _priv: () },
-                callbacks: Mutex::new(Callbacks {
-                    callbacks: HashMap::new(),
-                    call_results: HashMap::new(),
-                }),
+                callbacks: Mutex::new(Callbacks::new()),
                 networking_sockets_data: Mutex::new(NetworkingSocketsData {
                     sockets: Default::default(),
                     independent_connections: Default::default(),
@@ -122,6 +130,26 @@ impl Server {
         unsafe { register_callback(&self.inner, f) }
     }
 
+    /// Returns a receiver that queues every callback of the given type.
+    ///
+    /// See `Client::subscribe`.
+    pub fn subscribe<C>(&self) -> CallbackReceiver<C, ServerManager>
+    where
+        C: Callback + Send + 'static,
+    {
+        unsafe { subscribe(&self.inner) }
+    }
+
+    /// Returns a `Stream` of every callback of the given type.
+    ///
+    /// See `Client::subscribe_stream`.
+    pub fn subscribe_stream<C>(&self) -> CallbackStream<C, ServerManager>
+    where
+        C: Callback + Send + 'static,
+    {
+        unsafe { subscribe_stream(&self.inner) }
+    }
+
     /// Returns the steam id of the current server
     pub fn steam_id(&self) -> SteamId {
         unsafe { SteamId(sys::SteamAPI_ISteamGameServer_GetSteamID(self.server)) }
@@ -325,6 +353,8 @@ impl Server {
     */
 }
 
+// The fake backend doesn't simulate game servers
+#[cfg(not(feature = "fake"))]
 #[test]
 #[serial]
 fn test() {
@@ -0,0 +1,556 @@
+use super::*;
+
+use std::str::FromStr;
+
+const ACCOUNT_ID_MASK: u64 = 0xFFFF_FFFF;
+const INSTANCE_SHIFT: u64 = 32;
+const INSTANCE_MASK: u64 = 0x000F_FFFF;
+const ACCOUNT_TYPE_SHIFT: u64 = 52;
+const ACCOUNT_TYPE_MASK: u64 = 0xF;
+const UNIVERSE_SHIFT: u64 = 56;
+
+/// A user's steam id
+///
+/// Besides the account id a steam id encodes the universe and type
+/// of the account along with an instance. Steam ids can be parsed
+/// from and formatted as the 64 bit decimal, Steam2 (`STEAM_0:1:123`)
+/// and Steam3 (`[U:1:123]`) forms. `Display` uses the 64 bit form.
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SteamId(pub(crate) u64);
+
+impl SteamId {
+    /// The instance used by individual accounts on the desktop client
+    pub const DESKTOP_INSTANCE: u32 = 1;
+    /// The instance used by individual accounts on the web
+    pub const WEB_INSTANCE: u32 = 4;
+    /// Instance flag of group chats that belong to a clan
+    pub const CLAN_CHAT_INSTANCE_FLAG: u32 = (INSTANCE_MASK as u32 + 1) >> 1;
+    /// Instance flag of lobbies
+    pub const LOBBY_INSTANCE_FLAG: u32 = (INSTANCE_MASK as u32 + 1) >> 2;
+    /// Instance flag of matchmaking lobbies
+    pub const MMS_LOBBY_INSTANCE_FLAG: u32 = (INSTANCE_MASK as u32 + 1) >> 3;
+
+    /// Creates a `SteamId` from a raw 64 bit value.
+    ///
+    /// May be useful for deserializing steam ids from
+    /// a network or save format.
+    pub fn from_raw(id: u64) -> SteamId {
+        SteamId(id)
+    }
+
+    /// Creates a `SteamId` from its parts.
+    ///
+    /// Only the lower 20 bits of the instance are used.
+    pub fn new(
+        account_id: AccountId,
+        instance: u32,
+        account_type: AccountType,
+        universe: Universe,
+    ) -> SteamId {
+        SteamId(
+            u64::from(account_id.0)
+                | (u64::from(instance) & INSTANCE_MASK) << INSTANCE_SHIFT
+                | (account_type as u64) << ACCOUNT_TYPE_SHIFT
+                | (universe as u64) << UNIVERSE_SHIFT,
+        )
+    }
+
+    /// Creates the steam id of an individual account in the public
+    /// universe, as used by regular users on the desktop client.
+    pub fn from_account_id(account_id: AccountId) -> SteamId {
+        SteamId::new(
+            account_id,
+            SteamId::DESKTOP_INSTANCE,
+            AccountType::Individual,
+            Universe::Public,
+        )
+    }
+
+    /// Returns the raw 64 bit value of the steam id
+    ///
+    /// May be useful for serializing steam ids over a
+    /// network or to a save format.
+    pub fn raw(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the account id for this steam id
+    pub fn account_id(&self) -> AccountId {
+        AccountId((self.0 & ACCOUNT_ID_MASK) as u32)
+    }
+
+    /// Returns the instance of the account.
+    ///
+    /// For chats this includes the `*_INSTANCE_FLAG`s.
+    pub fn instance(&self) -> u32 {
+        ((self.0 >> INSTANCE_SHIFT) & INSTANCE_MASK) as u32
+    }
+
+    /// Returns the type of the account.
+    ///
+    /// Unknown types are returned as `AccountType::Invalid`.
+    pub fn account_type(&self) -> AccountType {
+        AccountType::from_raw(((self.0 >> ACCOUNT_TYPE_SHIFT) & ACCOUNT_TYPE_MASK) as u8)
+    }
+
+    /// Returns the universe the account belongs to.
+    ///
+    /// Unknown universes are returned as `Universe::Invalid`.
+    pub fn universe(&self) -> Universe {
+        Universe::from_raw((self.0 >> UNIVERSE_SHIFT) as u8)
+    }
+
+    /// Returns whether this could be the steam id of an existing account.
+    ///
+    /// This only checks that the parts are consistent with each other,
+    /// not that the account exists.
+    pub fn is_valid(&self) -> bool {
+        let account_type = self.account_type();
+        if account_type == AccountType::Invalid || self.universe() == Universe::Invalid {
+            return false;
+        }
+        let account_id = self.account_id().raw();
+        match account_type {
+            AccountType::Individual => account_id != 0 && self.instance() <= SteamId::WEB_INSTANCE,
+            AccountType::Clan => account_id != 0 && self.instance() == 0,
+            AccountType::GameServer => account_id != 0,
+            _ => true,
+        }
+    }
+
+    /// Returns whether this is the steam id of a user
+    pub fn is_individual(&self) -> bool {
+        self.account_type() == AccountType::Individual
+    }
+
+    /// Returns whether this is the steam id of a clan (a steam group)
+    pub fn is_clan(&self) -> bool {
+        self.account_type() == AccountType::Clan
+    }
+
+    /// Returns whether this is the steam id of a chat room
+    pub fn is_chat(&self) -> bool {
+        self.account_type() == AccountType::Chat
+    }
+
+    /// Returns whether this is the steam id of a lobby
+    pub fn is_lobby(&self) -> bool {
+        self.is_chat() && self.instance() & SteamId::LOBBY_INSTANCE_FLAG != 0
+    }
+
+    /// Returns whether this is the steam id of a persistent or
+    /// anonymous game server
+    pub fn is_game_server(&self) -> bool {
+        matches!(
+            self.account_type(),
+            AccountType::GameServer | AccountType::AnonGameServer
+        )
+    }
+
+    /// Returns whether this is the steam id of an anonymous game server
+    pub fn is_anonymous_game_server(&self) -> bool {
+        self.account_type() == AccountType::AnonGameServer
+    }
+
+    /// Returns the formatted SteamID32 string for this steam id.
+    ///
+    /// This is the legacy Steam2 form, e.g. `STEAM_0:1:40314158`.
+    pub fn steamid32(&self) -> String {
+        let account_id = self.account_id().raw();
+        let last_bit = account_id & 1;
+        format!("STEAM_0:{}:{}", last_bit, (account_id >> 1))
+    }
+
+    /// Returns the Steam3 string for this steam id, e.g. `[U:1:80628317]`.
+    ///
+    /// Like steam's own rendering this drops the matchmaking lobby flag,
+    /// so lobbies don't round trip through this form.
+    pub fn steam3(&self) -> String {
+        let account_type = self.account_type();
+        let instance = self.instance();
+        let letter = match account_type {
+            AccountType::Chat if instance & SteamId::CLAN_CHAT_INSTANCE_FLAG != 0 => 'c',
+            AccountType::Chat if instance & SteamId::LOBBY_INSTANCE_FLAG != 0 => 'L',
+            _ => account_type.steam3_letter(),
+        };
+        let show_instance = match account_type {
+            AccountType::AnonGameServer => true,
+            AccountType::Individual => instance != SteamId::DESKTOP_INSTANCE,
+            _ => false,
+        };
+        if show_instance {
+            format!(
+                "[{}:{}:{}:{}]",
+                letter,
+                self.universe() as u8,
+                self.account_id().raw(),
+                instance
+            )
+        } else {
+            format!(
+                "[{}:{}:{}]",
+                letter,
+                self.universe() as u8,
+                self.account_id().raw()
+            )
+        }
+    }
+
+    fn parse_steam2(s: &str) -> Option<SteamId> {
+        let mut parts = s.strip_prefix("STEAM_")?.split(':');
+        let universe = parts.next()?.parse::<u8>().ok()?;
+        let low_bit = parts.next()?.parse::<u32>().ok()?;
+        let high_bits = parts.next()?.parse::<u32>().ok()?;
+        if parts.next().is_some() || low_bit > 1 || high_bits > u32::MAX >> 1 {
+            return None;
+        }
+        // Old versions of the source engine always print universe 0
+        let universe = match universe {
+            0 => Universe::Public,
+            universe => Universe::from_raw(universe),
+        };
+        Some(SteamId::new(
+            AccountId(high_bits << 1 | low_bit),
+            SteamId::DESKTOP_INSTANCE,
+            AccountType::Individual,
+            universe,
+        ))
+    }
+
+    fn parse_steam3(s: &str) -> Option<SteamId> {
+        let mut parts = s.strip_prefix('[')?.strip_suffix(']')?.split(':');
+        let mut letter = parts.next()?.chars();
+        let letter = match (letter.next(), letter.next()) {
+            (Some(letter), None) => letter,
+            _ => return None,
+        };
+        let universe = Universe::from_raw(parts.next()?.parse::<u8>().ok()?);
+        let account_id = AccountId(parts.next()?.parse::<u32>().ok()?);
+        let instance = match parts.next() {
+            Some(instance) => Some(instance.parse::<u32>().ok()?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let (account_type, flags) = match letter {
+            'c' => (AccountType::Chat, SteamId::CLAN_CHAT_INSTANCE_FLAG),
+            'L' => (AccountType::Chat, SteamId::LOBBY_INSTANCE_FLAG),
+            letter => (AccountType::from_steam3_letter(letter)?, 0),
+        };
+        let instance = match (instance, account_type) {
+            (Some(instance), _) => instance,
+            (None, AccountType::Clan) | (None, AccountType::Chat) => 0,
+            (None, _) => SteamId::DESKTOP_INSTANCE,
+        };
+        if instance > INSTANCE_MASK as u32 {
+            return None;
+        }
+        Some(SteamId::new(
+            account_id,
+            instance | flags,
+            account_type,
+            universe,
+        ))
+    }
+}
+
+impl fmt::Display for SteamId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for SteamId {
+    type Err = ParseSteamIdError;
+
+    /// Parses a steam id from its 64 bit decimal, Steam2 or Steam3 form
+    fn from_str(s: &str) -> Result<SteamId, ParseSteamIdError> {
+        let s = s.trim();
+        let id = if s.starts_with("STEAM_") {
+            SteamId::parse_steam2(s)
+        } else if s.starts_with('[') {
+            SteamId::parse_steam3(s)
+        } else {
+            s.parse::<u64>().ok().map(SteamId)
+        };
+        id.ok_or(ParseSteamIdError)
+    }
+}
+
+/// Returned when a string isn't a steam id in any of the supported forms
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+#[error("invalid steam id")]
+pub struct ParseSteamIdError;
+
+/// The steam universe an account belongs to
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Universe {
+    Invalid = 0,
+    Public = 1,
+    Beta = 2,
+    Internal = 3,
+    Dev = 4,
+}
+
+impl Universe {
+    fn from_raw(raw: u8) -> Universe {
+        match raw {
+            1 => Universe::Public,
+            2 => Universe::Beta,
+            3 => Universe::Internal,
+            4 => Universe::Dev,
+            _ => Universe::Invalid,
+        }
+    }
+}
+
+/// The type of account a steam id refers to
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AccountType {
+    Invalid = 0,
+    /// A single user account
+    Individual = 1,
+    /// A multiseat (e.g. cybercafe) account
+    Multiseat = 2,
+    /// A persistent game server account
+    GameServer = 3,
+    /// An anonymous game server account
+    AnonGameServer = 4,
+    /// A pending account
+    Pending = 5,
+    /// A content server account
+    ContentServer = 6,
+    /// A steam group
+    Clan = 7,
+    /// A chat room or lobby
+    Chat = 8,
+    /// A fake account for a local console user
+    ConsoleUser = 9,
+    /// An anonymous user account
+    AnonUser = 10,
+}
+
+impl AccountType {
+    fn from_raw(raw: u8) -> AccountType {
+        match raw {
+            1 => AccountType::Individual,
+            2 => AccountType::Multiseat,
+            3 => AccountType::GameServer,
+            4 => AccountType::AnonGameServer,
+            5 => AccountType::Pending,
+            6 => AccountType::ContentServer,
+            7 => AccountType::Clan,
+            8 => AccountType::Chat,
+            9 => AccountType::ConsoleUser,
+            10 => AccountType::AnonUser,
+            _ => AccountType::Invalid,
+        }
+    }
+
+    fn steam3_letter(self) -> char {
+        match self {
+            AccountType::Invalid | AccountType::ConsoleUser => 'I',
+            AccountType::Individual => 'U',
+            AccountType::Multiseat => 'M',
+            AccountType::GameServer => 'G',
+            AccountType::AnonGameServer => 'A',
+            AccountType::Pending => 'P',
+            AccountType::ContentServer => 'C',
+            AccountType::Clan => 'g',
+            AccountType::Chat => 'T',
+            AccountType::AnonUser => 'a',
+        }
+    }
+
+    fn from_steam3_letter(letter: char) -> Option<AccountType> {
+        Some(match letter {
+            'I' | 'i' => AccountType::Invalid,
+            'U' => AccountType::Individual,
+            'M' => AccountType::Multiseat,
+            'G' => AccountType::GameServer,
+            'A' => AccountType::AnonGameServer,
+            'P' => AccountType::Pending,
+            'C' => AccountType::ContentServer,
+            'g' => AccountType::Clan,
+            'T' => AccountType::Chat,
+            'a' => AccountType::AnonUser,
+            _ => return None,
+        })
+    }
+}
+
+/// A user's account id
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountId(pub(crate) u32);
+
+impl AccountId {
+    /// Creates an `AccountId` from a raw 32 bit value.
+    ///
+    /// May be useful for deserializing account ids from
+    /// a network or save format.
+    pub fn from_raw(id: u32) -> AccountId {
+        AccountId(id)
+    }
+
+    /// Returns the raw 32 bit value of the steam id
+    ///
+    /// May be useful for serializing steam ids over a
+    /// network or to a save format.
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts() {
+        let id = SteamId(76561198040894045);
+        assert_eq!(id.account_id(), AccountId(80628317));
+        assert_eq!(id.instance(), SteamId::DESKTOP_INSTANCE);
+        assert_eq!(id.account_type(), AccountType::Individual);
+        assert_eq!(id.universe(), Universe::Public);
+        assert_eq!(SteamId::from_account_id(AccountId(80628317)), id);
+        assert!(id.is_valid());
+        assert!(id.is_individual());
+        assert!(!id.is_clan() && !id.is_lobby() && !id.is_game_server());
+
+        let lobby = SteamId(109775240917176337);
+        assert_eq!(lobby.account_type(), AccountType::Chat);
+        assert!(lobby.is_lobby());
+        assert!(lobby.is_valid());
+
+        let server = SteamId::new(
+            AccountId(1234),
+            5,
+            AccountType::AnonGameServer,
+            Universe::Public,
+        );
+        assert!(server.is_anonymous_game_server());
+        assert!(server.is_game_server());
+        assert_eq!(server.instance(), 5);
+
+        assert!(!SteamId(0).is_valid());
+        assert!(
+            !SteamId::new(AccountId(0), 1, AccountType::Individual, Universe::Public).is_valid()
+        );
+        assert!(!SteamId::new(AccountId(7), 1, AccountType::Clan, Universe::Public).is_valid());
+    }
+
+    #[test]
+    fn format() {
+        let id = SteamId(76561198040894045);
+        assert_eq!(id.to_string(), "76561198040894045");
+        assert_eq!(id.steamid32(), "STEAM_0:1:40314158");
+        assert_eq!(id.steam3(), "[U:1:80628317]");
+
+        let web = SteamId::new(
+            AccountId(80628317),
+            4,
+            AccountType::Individual,
+            Universe::Public,
+        );
+        assert_eq!(web.steam3(), "[U:1:80628317:4]");
+
+        let clan = SteamId::new(AccountId(4), 0, AccountType::Clan, Universe::Public);
+        assert_eq!(clan.steam3(), "[g:1:4]");
+
+        let lobby = SteamId(109775240917176337);
+        assert_eq!(
+            lobby.steam3(),
+            format!("[L:1:{}]", lobby.account_id().raw())
+        );
+
+        let server = SteamId::new(
+            AccountId(1234),
+            5,
+            AccountType::AnonGameServer,
+            Universe::Public,
+        );
+        assert_eq!(server.steam3(), "[A:1:1234:5]");
+    }
+
+    #[test]
+    fn parse() {
+        let id = SteamId(76561198040894045);
+        assert_eq!("76561198040894045".parse(), Ok(id));
+        assert_eq!("STEAM_0:1:40314158".parse(), Ok(id));
+        assert_eq!("STEAM_1:1:40314158".parse(), Ok(id));
+        assert_eq!("[U:1:80628317]".parse(), Ok(id));
+        assert_eq!(" [U:1:80628317] ".parse(), Ok(id));
+
+        let clan: SteamId = "[g:1:4]".parse().unwrap();
+        assert!(clan.is_clan() && clan.is_valid());
+        assert_eq!(clan.account_id(), AccountId(4));
+
+        let lobby = SteamId::new(
+            AccountId(20497),
+            SteamId::LOBBY_INSTANCE_FLAG,
+            AccountType::Chat,
+            Universe::Public,
+        );
+        assert_eq!(lobby.steam3().parse(), Ok(lobby));
+
+        let server: SteamId = "[A:1:1234:5]".parse().unwrap();
+        assert!(server.is_anonymous_game_server());
+        assert_eq!(server.instance(), 5);
+
+        for invalid in [
+            "",
+            "STEAM_0:2:1",
+            "STEAM_0:1",
+            "[U:1]",
+            "[X:1:4]",
+            "[U:1:4:5:6]",
+            "[U:1:4",
+            "U:1:4]",
+            "steam",
+            "-1",
+        ] {
+            assert_eq!(
+                invalid.parse::<SteamId>(),
+                Err(ParseSteamIdError),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for id in [
+            SteamId(76561198040894045),
+            SteamId::new(
+                AccountId(20497),
+                SteamId::LOBBY_INSTANCE_FLAG,
+                AccountType::Chat,
+                Universe::Public,
+            ),
+            SteamId::new(AccountId(4), 0, AccountType::Clan, Universe::Public),
+            SteamId::new(
+                AccountId(80628317),
+                4,
+                AccountType::Individual,
+                Universe::Beta,
+            ),
+            SteamId::new(
+                AccountId(1234),
+                5,
+                AccountType::AnonGameServer,
+                Universe::Public,
+            ),
+            SteamId::new(AccountId(77), 1, AccountType::GameServer, Universe::Dev),
+        ] {
+            assert_eq!(id.to_string().parse(), Ok(id));
+            assert_eq!(id.steam3().parse(), Ok(id), "{}", id.steam3());
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Internal helpers for the optional `tracing` feature.
+//!
+//! The macros expand to nothing without the feature, so the rest of the
+//! crate can emit events without sprinkling `cfg` attributes around.
+
+/// Emits a `tracing` event at the given level if the feature is enabled
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    }};
+}
+
+/// Reports an error that can't be returned to the caller, as a
+/// `tracing` event if the feature is enabled and on stderr otherwise
+macro_rules! report_error {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::error!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        eprintln!($($arg)+);
+    }};
+}
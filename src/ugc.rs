@@ -388,7 +388,30 @@ impl<Manager> UGC<Manager> {
     }
 
     /// Creates a workshop item
-    pub fn create_item<F>(&self, app_id: AppId, file_type: FileType, cb: F)
+    pub fn create_item<F>(
+        &self,
+        app_id: AppId,
+        file_type: FileType,
+        cb: F,
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<(PublishedFileId, bool), SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.create_item_call(app_id, file_type, cb))
+    }
+
+    /// Async version of [`create_item`](#method.create_item)
+    pub fn create_item_async(
+        &self,
+        app_id: AppId,
+        file_type: FileType,
+    ) -> CallResultFuture<Result<(PublishedFileId, bool), SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.create_item_call(app_id, file_type, cb)
+        })
+    }
+
+    fn create_item_call<F>(&self, app_id: AppId, file_type: FileType, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<(PublishedFileId, bool), SteamError>) + 'static + Send,
     {
@@ -398,19 +421,20 @@ impl<Manager> UGC<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 3,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        Err(v.m_eResult.into())
-                    } else {
-                        Ok((
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if v.m_eResult != sys::EResult::k_EResultOK => {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(v) => Ok((
                             PublishedFileId(v.m_nPublishedFileId),
                             v.m_bUserNeedsToAcceptWorkshopLegalAgreement,
-                        ))
+                        )),
                     })
                 },
             );
+            api_call
         }
     }
 
@@ -433,7 +457,32 @@ impl<Manager> UGC<Manager> {
     }
 
     /// Subscribes to a workshop item
-    pub fn subscribe_item<F>(&self, published_file_id: PublishedFileId, cb: F)
+    pub fn subscribe_item<F>(
+        &self,
+        published_file_id: PublishedFileId,
+        cb: F,
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.subscribe_item_call(published_file_id, cb))
+    }
+
+    /// Async version of [`subscribe_item`](#method.subscribe_item)
+    pub fn subscribe_item_async(
+        &self,
+        published_file_id: PublishedFileId,
+    ) -> CallResultFuture<Result<(), SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.subscribe_item_call(published_file_id, cb)
+        })
+    }
+
+    fn subscribe_item_call<F>(
+        &self,
+        published_file_id: PublishedFileId,
+        cb: F,
+    ) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<(), SteamError>) + 'static + Send,
     {
@@ -443,20 +492,49 @@ impl<Manager> UGC<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_REMOTE_STORAGE_BASE_ID + 13,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        Err(v.m_eResult.into())
-                    } else {
-                        Ok(())
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if v.m_eResult != sys::EResult::k_EResultOK => {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(_) => Ok(()),
                     })
                 },
             );
+            api_call
         }
     }
 
-    pub fn unsubscribe_item<F>(&self, published_file_id: PublishedFileId, cb: F)
+    pub fn unsubscribe_item<F>(
+        &self,
+        published_file_id: PublishedFileId,
+        cb: F,
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(
+            &self.inner,
+            self.unsubscribe_item_call(published_file_id, cb),
+        )
+    }
+
+    /// Async version of [`unsubscribe_item`](#method.unsubscribe_item)
+    pub fn unsubscribe_item_async(
+        &self,
+        published_file_id: PublishedFileId,
+    ) -> CallResultFuture<Result<(), SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.unsubscribe_item_call(published_file_id, cb)
+        })
+    }
+
+    fn unsubscribe_item_call<F>(
+        &self,
+        published_file_id: PublishedFileId,
+        cb: F,
+    ) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<(), SteamError>) + 'static + Send,
     {
@@ -466,16 +544,17 @@ impl<Manager> UGC<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_REMOTE_STORAGE_BASE_ID + 15,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        Err(v.m_eResult.into())
-                    } else {
-                        Ok(())
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if v.m_eResult != sys::EResult::k_EResultOK => {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(_) => Ok(()),
                     })
                 },
             );
+            api_call
         }
     }
 
@@ -630,28 +709,51 @@ impl<Manager> UGC<Manager> {
     }
 
     /// **DELETES** the item from the Steam Workshop.
-    pub fn delete_item<F>(&self, published_file_id: PublishedFileId, cb: F)
+    pub fn delete_item<F>(
+        &self,
+        published_file_id: PublishedFileId,
+        cb: F,
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<(), SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.delete_item_call(published_file_id, cb))
+    }
+
+    /// Async version of [`delete_item`](#method.delete_item)
+    pub fn delete_item_async(
+        &self,
+        published_file_id: PublishedFileId,
+    ) -> CallResultFuture<Result<(), SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.delete_item_call(published_file_id, cb)
+        })
+    }
+
+    fn delete_item_call<F>(&self, published_file_id: PublishedFileId, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<(), SteamError>) + 'static + Send,
     {
         unsafe {
             let api_call = sys::SteamAPI_ISteamUGC_DeleteItem(self.ugc, published_file_id.0);
-            register_call_result::<sys::DownloadItemResult_t, _, _>(
+            register_call_result::<sys::RemoteStorageDeletePublishedFileResult_t, _, _>(
                 &self.inner,
                 api_call,
                 CALLBACK_REMOTE_STORAGE_BASE_ID + 17,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else if v.m_eResult != sys::EResult::k_EResultNone
-                        && v.m_eResult != sys::EResult::k_EResultOK
-                    {
-                        Err(v.m_eResult.into())
-                    } else {
-                        Ok(())
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v)
+                            if v.m_eResult != sys::EResult::k_EResultNone
+                                && v.m_eResult != sys::EResult::k_EResultOK =>
+                        {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(_) => Ok(()),
                     })
                 },
             );
+            api_call
         }
     }
 }
@@ -812,6 +914,32 @@ impl<Manager> UpdateHandle<Manager> {
     }
 
     pub fn submit<F>(self, change_note: Option<&str>, cb: F) -> UpdateWatchHandle<Manager>
+    where
+        F: FnOnce(Result<(PublishedFileId, bool), SteamError>) + 'static + Send,
+    {
+        let api_call = self.submit_call(change_note, cb);
+        let call_result = CallResultHandle::new(&self.inner, api_call);
+        self.into_watch_handle(call_result)
+    }
+
+    /// Async version of [`submit`](#method.submit)
+    ///
+    /// Returns the watch handle together with a future that resolves
+    /// once the update has been submitted.
+    #[allow(clippy::type_complexity)]
+    pub fn submit_async(
+        self,
+        change_note: Option<&str>,
+    ) -> (
+        UpdateWatchHandle<Manager>,
+        CallResultFuture<Result<(PublishedFileId, bool), SteamError>, Manager>,
+    ) {
+        let future = CallResultFuture::new(&self.inner, |cb| self.submit_call(change_note, cb));
+        let call_result = future.shared_handle();
+        (self.into_watch_handle(call_result), future)
+    }
+
+    fn submit_call<F>(&self, change_note: Option<&str>, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<(PublishedFileId, bool), SteamError>) + 'static + Send,
     {
@@ -824,24 +952,32 @@ impl<Manager> UpdateHandle<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 4,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        Err(v.m_eResult.into())
-                    } else {
-                        Ok((
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) if v.m_eResult != sys::EResult::k_EResultOK => {
+                            Err(v.m_eResult.into())
+                        }
+                        Ok(v) => Ok((
                             PublishedFileId(v.m_nPublishedFileId),
                             v.m_bUserNeedsToAcceptWorkshopLegalAgreement,
-                        ))
+                        )),
                     })
                 },
             );
+            api_call
         }
+    }
+
+    fn into_watch_handle(
+        self,
+        call_result: CallResultHandle<Manager>,
+    ) -> UpdateWatchHandle<Manager> {
         UpdateWatchHandle {
             ugc: self.ugc,
             _inner: self.inner,
             handle: self.handle,
+            call_result,
         }
     }
 }
@@ -852,12 +988,18 @@ pub struct UpdateWatchHandle<Manager> {
     _inner: Arc<Inner<Manager>>,
 
     handle: sys::UGCUpdateHandle_t,
+    call_result: CallResultHandle<Manager>,
 }
 
 unsafe impl<Manager> Send for UpdateWatchHandle<Manager> {}
 unsafe impl<Manager> Sync for UpdateWatchHandle<Manager> {}
 
 impl<Manager> UpdateWatchHandle<Manager> {
+    /// Returns the handle of the pending submit result
+    pub fn call_result(&self) -> &CallResultHandle<Manager> {
+        &self.call_result
+    }
+
     pub fn progress(&self) -> (UpdateStatus, u64, u64) {
         unsafe {
             let mut progress = 0;
@@ -1037,9 +1179,25 @@ impl<Manager> UserListQuery<Manager> {
     }
 
     /// Runs the query
-    pub fn fetch<F>(mut self, cb: F)
+    pub fn fetch<F>(self, cb: F) -> CallResultHandle<Manager>
     where
         F: for<'a> FnOnce(Result<QueryResults<'a>, SteamError>) + 'static + Send,
+    {
+        let inner = Arc::clone(&self.inner);
+        CallResultHandle::new(&inner, self.fetch_call(cb))
+    }
+
+    /// Async version of [`fetch`](#method.fetch)
+    pub fn fetch_async(
+        self,
+    ) -> CallResultFuture<Result<QueryResults<'static>, SteamError>, Manager> {
+        let inner = Arc::clone(&self.inner);
+        CallResultFuture::new(&inner, |cb| self.fetch_call(cb))
+    }
+
+    fn fetch_call<F>(mut self, cb: F) -> sys::SteamAPICall_t
+    where
+        F: FnOnce(Result<QueryResults<'static>, SteamError>) + 'static + Send,
     {
         let ugc = self.ugc;
         let inner = Arc::clone(&self.inner);
@@ -1052,17 +1210,21 @@ impl<Manager> UserListQuery<Manager> {
                 &inner,
                 api_call,
                 CALLBACK_BASE_ID + 1,
-                move |v, io_error| {
+                move |v| {
                     let ugc = sys::SteamAPI_SteamUGC_v016();
-                    if io_error {
-                        sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
-                        cb(Err(SteamError::IOFailure));
-                        return;
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
-                        cb(Err(v.m_eResult.into()));
-                        return;
-                    }
+                    let v = match v {
+                        Ok(v) if v.m_eResult == sys::EResult::k_EResultOK => v,
+                        Ok(v) => {
+                            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
+                            cb(Err(v.m_eResult.into()));
+                            return;
+                        }
+                        Err(err) => {
+                            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
+                            cb(Err(err));
+                            return;
+                        }
+                    };
 
                     let result = QueryResults {
                         ugc,
@@ -1075,34 +1237,43 @@ impl<Manager> UserListQuery<Manager> {
                     cb(Ok(result));
                 },
             );
+            api_call
         }
     }
 
     /// Runs the query, only fetching the total number of results.
-    pub fn fetch_total<F>(self, cb: F)
+    pub fn fetch_total<F>(self, cb: F) -> CallResultHandle<Manager>
     where
         F: Fn(Result<u32, SteamError>) + 'static + Send,
     {
+        self.total_only()
+            .fetch(move |res| cb(res.map(|qr| qr.total_results())))
+    }
+
+    /// Async version of [`fetch_total`](#method.fetch_total)
+    pub fn fetch_total_async(self) -> CallResultFuture<Result<u32, SteamError>, Manager> {
+        let inner = Arc::clone(&self.inner);
+        CallResultFuture::new(&inner, |cb| {
+            self.total_only()
+                .fetch_call(move |res| cb(res.map(|qr| qr.total_results())))
+        })
+    }
+
+    fn total_only(self) -> Self {
         unsafe {
             let ok =
                 sys::SteamAPI_ISteamUGC_SetReturnTotalOnly(self.ugc, self.handle.unwrap(), true);
             debug_assert!(ok);
         }
-
-        self.fetch(move |res| cb(res.map(|qr| qr.total_results())))
+        self
     }
 
     /// Runs the query, only fetching the IDs.
-    pub fn fetch_ids<F>(self, cb: F)
+    pub fn fetch_ids<F>(self, cb: F) -> CallResultHandle<Manager>
     where
         F: Fn(Result<Vec<PublishedFileId>, SteamError>) + 'static + Send,
     {
-        unsafe {
-            let ok = sys::SteamAPI_ISteamUGC_SetReturnOnlyIDs(self.ugc, self.handle.unwrap(), true);
-            debug_assert!(ok);
-        }
-
-        self.fetch(move |res| {
+        self.ids_only().fetch(move |res| {
             cb(res.map(|qr| {
                 qr.iter()
                     .filter_map(|v| v.map(|v| PublishedFileId(v.published_file_id.0)))
@@ -1110,6 +1281,30 @@ impl<Manager> UserListQuery<Manager> {
             }))
         })
     }
+
+    /// Async version of [`fetch_ids`](#method.fetch_ids)
+    pub fn fetch_ids_async(
+        self,
+    ) -> CallResultFuture<Result<Vec<PublishedFileId>, SteamError>, Manager> {
+        let inner = Arc::clone(&self.inner);
+        CallResultFuture::new(&inner, |cb| {
+            self.ids_only().fetch_call(move |res| {
+                cb(res.map(|qr| {
+                    qr.iter()
+                        .filter_map(|v| v.map(|v| PublishedFileId(v.published_file_id.0)))
+                        .collect::<Vec<_>>()
+                }))
+            })
+        })
+    }
+
+    fn ids_only(self) -> Self {
+        unsafe {
+            let ok = sys::SteamAPI_ISteamUGC_SetReturnOnlyIDs(self.ugc, self.handle.unwrap(), true);
+            debug_assert!(ok);
+        }
+        self
+    }
 }
 
 /// Query object from `query_items`, to allow for more filtering.
@@ -1221,9 +1416,25 @@ impl<Manager> ItemListDetailsQuery<Manager> {
     }
 
     /// Runs the query
-    pub fn fetch<F>(mut self, cb: F)
+    pub fn fetch<F>(self, cb: F) -> CallResultHandle<Manager>
     where
         F: for<'a> FnOnce(Result<QueryResults<'a>, SteamError>) + 'static + Send,
+    {
+        let inner = Arc::clone(&self.inner);
+        CallResultHandle::new(&inner, self.fetch_call(cb))
+    }
+
+    /// Async version of [`fetch`](#method.fetch)
+    pub fn fetch_async(
+        self,
+    ) -> CallResultFuture<Result<QueryResults<'static>, SteamError>, Manager> {
+        let inner = Arc::clone(&self.inner);
+        CallResultFuture::new(&inner, |cb| self.fetch_call(cb))
+    }
+
+    fn fetch_call<F>(mut self, cb: F) -> sys::SteamAPICall_t
+    where
+        F: FnOnce(Result<QueryResults<'static>, SteamError>) + 'static + Send,
     {
         let ugc = self.ugc;
         let inner = Arc::clone(&self.inner);
@@ -1236,17 +1447,21 @@ impl<Manager> ItemListDetailsQuery<Manager> {
                 &inner,
                 api_call,
                 CALLBACK_BASE_ID + 1,
-                move |v, io_error| {
+                move |v| {
                     let ugc = sys::SteamAPI_SteamUGC_v016();
-                    if io_error {
-                        sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
-                        cb(Err(SteamError::IOFailure));
-                        return;
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
-                        cb(Err(v.m_eResult.into()));
-                        return;
-                    }
+                    let v = match v {
+                        Ok(v) if v.m_eResult == sys::EResult::k_EResultOK => v,
+                        Ok(v) => {
+                            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
+                            cb(Err(v.m_eResult.into()));
+                            return;
+                        }
+                        Err(err) => {
+                            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
+                            cb(Err(err));
+                            return;
+                        }
+                    };
 
                     let result = QueryResults {
                         ugc,
@@ -1259,21 +1474,35 @@ impl<Manager> ItemListDetailsQuery<Manager> {
                     cb(Ok(result));
                 },
             );
+            api_call
         }
     }
 
     /// Runs the query, only fetching the total number of results.
-    pub fn fetch_total<F>(self, cb: F)
+    pub fn fetch_total<F>(self, cb: F) -> CallResultHandle<Manager>
     where
         F: Fn(Result<u32, SteamError>) + 'static + Send,
     {
+        self.total_only()
+            .fetch(move |res| cb(res.map(|qr| qr.total_results())))
+    }
+
+    /// Async version of [`fetch_total`](#method.fetch_total)
+    pub fn fetch_total_async(self) -> CallResultFuture<Result<u32, SteamError>, Manager> {
+        let inner = Arc::clone(&self.inner);
+        CallResultFuture::new(&inner, |cb| {
+            self.total_only()
+                .fetch_call(move |res| cb(res.map(|qr| qr.total_results())))
+        })
+    }
+
+    fn total_only(self) -> Self {
         unsafe {
             let ok =
                 sys::SteamAPI_ISteamUGC_SetReturnTotalOnly(self.ugc, self.handle.unwrap(), true);
             debug_assert!(ok);
         }
-
-        self.fetch(move |res| cb(res.map(|qr| qr.total_results())))
+        self
     }
 }
 
@@ -1369,9 +1598,25 @@ impl<Manager> ItemDetailsQuery<Manager> {
     }
 
     /// Runs the query
-    pub fn fetch<F>(mut self, cb: F)
+    pub fn fetch<F>(self, cb: F) -> CallResultHandle<Manager>
     where
         F: for<'a> FnOnce(Result<QueryResults<'a>, SteamError>) + 'static + Send,
+    {
+        let inner = Arc::clone(&self.inner);
+        CallResultHandle::new(&inner, self.fetch_call(cb))
+    }
+
+    /// Async version of [`fetch`](#method.fetch)
+    pub fn fetch_async(
+        self,
+    ) -> CallResultFuture<Result<QueryResults<'static>, SteamError>, Manager> {
+        let inner = Arc::clone(&self.inner);
+        CallResultFuture::new(&inner, |cb| self.fetch_call(cb))
+    }
+
+    fn fetch_call<F>(mut self, cb: F) -> sys::SteamAPICall_t
+    where
+        F: FnOnce(Result<QueryResults<'static>, SteamError>) + 'static + Send,
     {
         let ugc = self.ugc;
         let inner = Arc::clone(&self.inner);
@@ -1384,17 +1629,21 @@ impl<Manager> ItemDetailsQuery<Manager> {
                 &inner,
                 api_call,
                 CALLBACK_BASE_ID + 1,
-                move |v, io_error| {
+                move |v| {
                     let ugc = sys::SteamAPI_SteamUGC_v016();
-                    if io_error {
-                        sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
-                        cb(Err(SteamError::IOFailure));
-                        return;
-                    } else if v.m_eResult != sys::EResult::k_EResultOK {
-                        sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
-                        cb(Err(v.m_eResult.into()));
-                        return;
-                    }
+                    let v = match v {
+                        Ok(v) if v.m_eResult == sys::EResult::k_EResultOK => v,
+                        Ok(v) => {
+                            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
+                            cb(Err(v.m_eResult.into()));
+                            return;
+                        }
+                        Err(err) => {
+                            sys::SteamAPI_ISteamUGC_ReleaseQueryUGCRequest(ugc, handle);
+                            cb(Err(err));
+                            return;
+                        }
+                    };
 
                     let result = QueryResults {
                         ugc,
@@ -1407,6 +1656,7 @@ impl<Manager> ItemDetailsQuery<Manager> {
                     cb(Ok(result));
                 },
             );
+            api_call
         }
     }
 }
@@ -1420,6 +1670,8 @@ pub struct QueryResults<'a> {
     was_cached: bool,
     _phantom: marker::PhantomData<&'a sys::ISteamUGC>,
 }
+unsafe impl Send for QueryResults<'_> {}
+
 impl<'a> Drop for QueryResults<'a> {
     fn drop(&mut self) {
         unsafe {
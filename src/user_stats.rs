@@ -15,7 +15,22 @@ pub struct UserStats<Manager> {
 const CALLBACK_BASE_ID: i32 = 1100;
 
 impl<Manager> UserStats<Manager> {
-    pub fn find_leaderboard<F>(&self, name: &str, cb: F)
+    pub fn find_leaderboard<F>(&self, name: &str, cb: F) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(&self.inner, self.find_leaderboard_call(name, cb))
+    }
+
+    /// Async version of [`find_leaderboard`](#method.find_leaderboard)
+    pub fn find_leaderboard_async(
+        &self,
+        name: &str,
+    ) -> CallResultFuture<Result<Option<Leaderboard>, SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| self.find_leaderboard_call(name, cb))
+    }
+
+    fn find_leaderboard_call<F>(&self, name: &str, cb: F) -> sys::SteamAPICall_t
     where
         F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
     {
@@ -29,18 +44,18 @@ impl<Manager> UserStats<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 4,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else {
-                        Ok(if v.m_bLeaderboardFound != 0 {
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) => Ok(if v.m_bLeaderboardFound != 0 {
                             Some(Leaderboard(v.m_hSteamLeaderboard))
                         } else {
                             None
-                        })
+                        }),
                     })
                 },
             );
+            api_call
         }
     }
 
@@ -50,7 +65,36 @@ impl<Manager> UserStats<Manager> {
         sort_method: LeaderboardSortMethod,
         display_type: LeaderboardDisplayType,
         cb: F,
-    ) where
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(
+            &self.inner,
+            self.find_or_create_leaderboard_call(name, sort_method, display_type, cb),
+        )
+    }
+
+    /// Async version of [`find_or_create_leaderboard`](#method.find_or_create_leaderboard)
+    pub fn find_or_create_leaderboard_async(
+        &self,
+        name: &str,
+        sort_method: LeaderboardSortMethod,
+        display_type: LeaderboardDisplayType,
+    ) -> CallResultFuture<Result<Option<Leaderboard>, SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.find_or_create_leaderboard_call(name, sort_method, display_type, cb)
+        })
+    }
+
+    fn find_or_create_leaderboard_call<F>(
+        &self,
+        name: &str,
+        sort_method: LeaderboardSortMethod,
+        display_type: LeaderboardDisplayType,
+        cb: F,
+    ) -> sys::SteamAPICall_t
+    where
         F: FnOnce(Result<Option<Leaderboard>, SteamError>) + 'static + Send,
     {
         unsafe {
@@ -87,18 +131,18 @@ impl<Manager> UserStats<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 4,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else {
-                        Ok(if v.m_bLeaderboardFound != 0 {
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) => Ok(if v.m_bLeaderboardFound != 0 {
                             Some(Leaderboard(v.m_hSteamLeaderboard))
                         } else {
                             None
-                        })
+                        }),
                     })
                 },
             );
+            api_call
         }
     }
 
@@ -109,7 +153,38 @@ impl<Manager> UserStats<Manager> {
         score: i32,
         details: &[i32],
         cb: F,
-    ) where
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<Option<LeaderboardScoreUploaded>, SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(
+            &self.inner,
+            self.upload_leaderboard_score_call(leaderboard, method, score, details, cb),
+        )
+    }
+
+    /// Async version of [`upload_leaderboard_score`](#method.upload_leaderboard_score)
+    pub fn upload_leaderboard_score_async(
+        &self,
+        leaderboard: &Leaderboard,
+        method: UploadScoreMethod,
+        score: i32,
+        details: &[i32],
+    ) -> CallResultFuture<Result<Option<LeaderboardScoreUploaded>, SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.upload_leaderboard_score_call(leaderboard, method, score, details, cb)
+        })
+    }
+
+    fn upload_leaderboard_score_call<F>(
+        &self,
+        leaderboard: &Leaderboard,
+        method: UploadScoreMethod,
+        score: i32,
+        details: &[i32],
+        cb: F,
+    ) -> sys::SteamAPICall_t
+    where
         F: FnOnce(Result<Option<LeaderboardScoreUploaded>, SteamError>) + 'static + Send,
     {
         unsafe {
@@ -133,11 +208,10 @@ impl<Manager> UserStats<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 6,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else {
-                        Ok(if v.m_bSuccess != 0 {
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) => Ok(if v.m_bSuccess != 0 {
                             Some(LeaderboardScoreUploaded {
                                 score: v.m_nScore,
                                 was_changed: v.m_bScoreChanged != 0,
@@ -146,10 +220,11 @@ impl<Manager> UserStats<Manager> {
                             })
                         } else {
                             None
-                        })
+                        }),
                     })
                 },
             );
+            api_call
         }
     }
 
@@ -161,7 +236,54 @@ impl<Manager> UserStats<Manager> {
         end: usize,
         max_details_len: usize,
         cb: F,
-    ) where
+    ) -> CallResultHandle<Manager>
+    where
+        F: FnOnce(Result<Vec<LeaderboardEntry>, SteamError>) + 'static + Send,
+    {
+        CallResultHandle::new(
+            &self.inner,
+            self.download_leaderboard_entries_call(
+                leaderboard,
+                request,
+                start,
+                end,
+                max_details_len,
+                cb,
+            ),
+        )
+    }
+
+    /// Async version of [`download_leaderboard_entries`](#method.download_leaderboard_entries)
+    pub fn download_leaderboard_entries_async(
+        &self,
+        leaderboard: &Leaderboard,
+        request: LeaderboardDataRequest,
+        start: usize,
+        end: usize,
+        max_details_len: usize,
+    ) -> CallResultFuture<Result<Vec<LeaderboardEntry>, SteamError>, Manager> {
+        CallResultFuture::new(&self.inner, |cb| {
+            self.download_leaderboard_entries_call(
+                leaderboard,
+                request,
+                start,
+                end,
+                max_details_len,
+                cb,
+            )
+        })
+    }
+
+    fn download_leaderboard_entries_call<F>(
+        &self,
+        leaderboard: &Leaderboard,
+        request: LeaderboardDataRequest,
+        start: usize,
+        end: usize,
+        max_details_len: usize,
+        cb: F,
+    ) -> sys::SteamAPICall_t
+    where
         F: FnOnce(Result<Vec<LeaderboardEntry>, SteamError>) + 'static + Send,
     {
         unsafe {
@@ -188,37 +310,39 @@ impl<Manager> UserStats<Manager> {
                 &self.inner,
                 api_call,
                 CALLBACK_BASE_ID + 5,
-                move |v, io_error| {
-                    cb(if io_error {
-                        Err(SteamError::IOFailure)
-                    } else {
-                        let len = v.m_cEntryCount;
-                        let mut entries = Vec::with_capacity(len as usize);
-                        for idx in 0..len {
-                            let mut entry: sys::LeaderboardEntry_t = std::mem::zeroed();
-                            let mut details = Vec::with_capacity(max_details_len);
-
-                            sys::SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
-                                user_stats as *mut _,
-                                v.m_hSteamLeaderboardEntries,
-                                idx,
-                                &mut entry,
-                                details.as_mut_ptr(),
-                                max_details_len as _,
-                            );
-                            details.set_len(entry.m_cDetails as usize);
-
-                            entries.push(LeaderboardEntry {
-                                user: SteamId(entry.m_steamIDUser.m_steamid.m_unAll64Bits),
-                                global_rank: entry.m_nGlobalRank,
-                                score: entry.m_nScore,
-                                details,
-                            })
+                move |v| {
+                    cb(match v {
+                        Err(err) => Err(err),
+                        Ok(v) => {
+                            let len = v.m_cEntryCount;
+                            let mut entries = Vec::with_capacity(len as usize);
+                            for idx in 0..len {
+                                let mut entry: sys::LeaderboardEntry_t = std::mem::zeroed();
+                                let mut details = Vec::with_capacity(max_details_len);
+
+                                sys::SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry(
+                                    user_stats as *mut _,
+                                    v.m_hSteamLeaderboardEntries,
+                                    idx,
+                                    &mut entry,
+                                    details.as_mut_ptr(),
+                                    max_details_len as _,
+                                );
+                                details.set_len(entry.m_cDetails as usize);
+
+                                entries.push(LeaderboardEntry {
+                                    user: SteamId(entry.m_steamIDUser.m_steamid.m_unAll64Bits),
+                                    global_rank: entry.m_nGlobalRank,
+                                    score: entry.m_nScore,
+                                    details,
+                                })
+                            }
+                            Ok(entries)
                         }
-                        Ok(entries)
                     })
                 },
             );
+            api_call
         }
     }
 
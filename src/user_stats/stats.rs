@@ -89,4 +89,18 @@ impl<M> AchievementHelper<'_, M> {
             Err(())
         }
     }
+
+    /// Returns the icon of the achievement, which is the locked or
+    /// unlocked icon depending on whether the user has it.
+    ///
+    /// Returns `None` if the icon is still being fetched.
+    pub fn icon(&self) -> Option<SteamImage> {
+        unsafe {
+            let handle = sys::SteamAPI_ISteamUserStats_GetAchievementIcon(
+                self.parent.user_stats,
+                self.name.as_ptr() as *const _,
+            );
+            load_image(sys::SteamAPI_SteamUtils_v010(), handle)
+        }
+    }
 }
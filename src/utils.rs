@@ -1,11 +1,11 @@
 use super::*;
 
 use std::ffi::CStr;
-use std::os::raw::c_char;
-use std::panic;
-use std::process::abort;
+use std::os::raw::{c_char, c_int};
 use std::sync::RwLock;
 
+const CALLBACK_BASE_ID: i32 = 700;
+
 /// Access to the steam utils interface
 pub struct Utils<Manager> {
     pub(crate) utils: *mut sys::ISteamUtils,
@@ -19,14 +19,105 @@ pub enum NotificationPosition {
     BottomRight,
 }
 
+/// The kind of text the gamepad text input dialog accepts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadTextInputMode {
+    Normal,
+    /// Hides the entered text
+    Password,
+}
+
+/// Whether the gamepad text input dialog accepts multiple lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadTextInputLineMode {
+    SingleLine,
+    MultipleLines,
+}
+
+/// The keyboard layout of the floating gamepad text input
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatingGamepadTextInputMode {
+    /// Enter dismisses the keyboard
+    SingleLine,
+    /// The keyboard has to be dismissed by the user
+    MultipleLines,
+    /// A keyboard with keys for email addresses
+    Email,
+    /// A numeric keypad
+    Numeric,
+}
+
+/// An image loaded from a steam image handle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SteamImage {
+    pub width: u32,
+    pub height: u32,
+    /// The pixels in row-major order, 4 bytes (RGBA) per pixel
+    pub rgba: Vec<u8>,
+}
+
+/// Fails with the original image if its buffer doesn't hold exactly
+/// `width * height` pixels
+#[cfg(feature = "image")]
+impl TryFrom<SteamImage> for image::RgbaImage {
+    type Error = SteamImage;
+
+    fn try_from(img: SteamImage) -> Result<image::RgbaImage, SteamImage> {
+        if img.rgba.len() as u64 != u64::from(img.width) * u64::from(img.height) * 4 {
+            return Err(img);
+        }
+        let SteamImage {
+            width,
+            height,
+            rgba,
+        } = img;
+        Ok(image::RgbaImage::from_raw(width, height, rgba).expect("buffer size was checked"))
+    }
+}
+
+/// Where a piece of text being filtered comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextFilteringContext {
+    Unknown,
+    /// Game content, only legally required filtering is applied
+    GameContent,
+    /// Chat from another player
+    Chat,
+    /// A character or item name
+    Name,
+}
+
 lazy_static! {
     /// Global rust warning callback
     static ref WARNING_CALLBACK: RwLock<Option<Box<dyn Fn(i32, &CStr) + Send + Sync>>> = RwLock::new(None);
+    /// Panics of the warning callback, until the next `run_callbacks`
+    static ref WARNING_PANICS: Mutex<Vec<CallbackPanic>> = Mutex::new(Vec::new());
+}
+
+pub(crate) fn take_warning_panics() -> Vec<CallbackPanic> {
+    match WARNING_PANICS.lock() {
+        Ok(mut panics) => std::mem::take(&mut *panics),
+        Err(_) => Vec::new(),
+    }
 }
 
 /// C function to pass as the real callback, which forwards to the `WARNING_CALLBACK` if any
-unsafe extern "C" fn c_warning_callback(level: i32, msg: *const c_char) {
-    let lock = WARNING_CALLBACK.read().expect("warning func lock poisoned");
+pub(crate) unsafe extern "C" fn c_warning_callback(level: i32, msg: *const c_char) {
+    #[cfg(feature = "tracing")]
+    {
+        let msg = CStr::from_ptr(msg).to_string_lossy();
+        let msg = msg.trim_end();
+        if level == 0 {
+            tracing::info!(target: "steamworks::sdk", "{}", msg);
+        } else {
+            tracing::warn!(target: "steamworks::sdk", "{}", msg);
+        }
+    }
+
+    let lock = match WARNING_CALLBACK.read() {
+        Ok(lock) => lock,
+        Err(_) => return,
+    };
     let cb = match lock.as_ref() {
         Some(cb) => cb,
         None => {
@@ -36,16 +127,12 @@ unsafe extern "C" fn c_warning_callback(level: i32, msg: *const c_char) {
 
     let s = CStr::from_ptr(msg);
 
-    let res = panic::catch_unwind(panic::AssertUnwindSafe(|| cb(level, s)));
-    if let Err(err) = res {
-        if let Some(err) = err.downcast_ref::<&str>() {
-            println!("Steam warning callback panicked: {}", err);
-        } else if let Some(err) = err.downcast_ref::<String>() {
-            println!("Steam warning callback panicked: {}", err);
-        } else {
-            println!("Steam warning callback panicked");
+    // Unwinding into steam is undefined behaviour, the panic is
+    // handed to the next `run_callbacks` instead
+    if let Err(panic) = CallbackPanic::catch(None, || cb(level, s)) {
+        if let Ok(mut panics) = WARNING_PANICS.lock() {
+            panics.push(panic);
         }
-        abort();
     }
 }
 
@@ -82,6 +169,204 @@ impl<Manager> Utils<Manager> {
         unsafe { sys::SteamAPI_ISteamUtils_GetServerRealTime(self.utils) }
     }
 
+    /// Returns whether steam is running on a Steam Deck
+    pub fn is_steam_running_on_steam_deck(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsSteamRunningOnSteamDeck(self.utils) }
+    }
+
+    /// Returns whether steam is running in Big Picture mode
+    pub fn is_steam_in_big_picture_mode(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsSteamInBigPictureMode(self.utils) }
+    }
+
+    /// Returns whether the game was launched through the Steam China
+    /// launcher
+    pub fn is_steam_china_launcher(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsSteamChinaLauncher(self.utils) }
+    }
+
+    /// Returns whether steam and the game are set up to stream to a
+    /// VR headset
+    pub fn is_vr_headset_streaming_enabled(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsVRHeadsetStreamingEnabled(self.utils) }
+    }
+
+    /// Returns whether the steam overlay is running and the user
+    /// can access it.
+    ///
+    /// The overlay takes a few seconds to start, so this may be
+    /// `false` right after `Client::init`.
+    pub fn is_overlay_enabled(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_IsOverlayEnabled(self.utils) }
+    }
+
+    /// Returns whether the overlay needs a frame to be presented.
+    ///
+    /// Games that only redraw on events should keep presenting frames
+    /// while this is `true` so the overlay stays responsive.
+    pub fn overlay_needs_present(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_BOverlayNeedsPresent(self.utils) }
+    }
+
+    /// Returns the number of seconds since the user last moved the
+    /// mouse or used the keyboard or a controller
+    pub fn seconds_since_app_active(&self) -> u32 {
+        unsafe { sys::SteamAPI_ISteamUtils_GetSecondsSinceAppActive(self.utils) }
+    }
+
+    /// Returns the current battery power in percent.
+    ///
+    /// Returns `None` if the computer is running on AC power.
+    pub fn current_battery_power(&self) -> Option<u8> {
+        match unsafe { sys::SteamAPI_ISteamUtils_GetCurrentBatteryPower(self.utils) } {
+            255 => None,
+            power => Some(power),
+        }
+    }
+
+    /// Opens the big picture text input dialog, which supports
+    /// gamepads.
+    ///
+    /// `GamepadTextInputDismissed` is posted with the entered text once
+    /// the user closes the dialog. Returns `false` if the dialog couldn't
+    /// be opened, e.g. because big picture mode isn't running.
+    pub fn show_gamepad_text_input(
+        &self,
+        input_mode: GamepadTextInputMode,
+        line_mode: GamepadTextInputLineMode,
+        description: &str,
+        max_chars: u32,
+        existing_text: &str,
+    ) -> bool {
+        let input_mode = match input_mode {
+            GamepadTextInputMode::Normal => {
+                sys::EGamepadTextInputMode::k_EGamepadTextInputModeNormal
+            }
+            GamepadTextInputMode::Password => {
+                sys::EGamepadTextInputMode::k_EGamepadTextInputModePassword
+            }
+        };
+        let line_mode = match line_mode {
+            GamepadTextInputLineMode::SingleLine => {
+                sys::EGamepadTextInputLineMode::k_EGamepadTextInputLineModeSingleLine
+            }
+            GamepadTextInputLineMode::MultipleLines => {
+                sys::EGamepadTextInputLineMode::k_EGamepadTextInputLineModeMultipleLines
+            }
+        };
+        let description = CString::new(description).unwrap();
+        let existing_text = CString::new(existing_text).unwrap();
+        unsafe {
+            sys::SteamAPI_ISteamUtils_ShowGamepadTextInput(
+                self.utils,
+                input_mode,
+                line_mode,
+                description.as_ptr(),
+                max_chars,
+                existing_text.as_ptr(),
+            )
+        }
+    }
+
+    /// Returns the text entered into the last gamepad text input dialog.
+    ///
+    /// `GamepadTextInputDismissed` already carries the text, this is
+    /// only needed to read it again later.
+    pub fn entered_gamepad_text_input(&self) -> Option<String> {
+        unsafe { entered_gamepad_text_input(self.utils) }
+    }
+
+    /// Opens the floating on-screen keyboard over the game's own text
+    /// field.
+    ///
+    /// The keyboard types into the game as if a real keyboard was used.
+    /// The position and size of the text field are in screen pixels and
+    /// are used to keep the keyboard from covering it.
+    /// `FloatingGamepadTextInputDismissed` is posted once it closes.
+    pub fn show_floating_gamepad_text_input(
+        &self,
+        mode: FloatingGamepadTextInputMode,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> bool {
+        use sys::EFloatingGamepadTextInputMode::*;
+        let mode = match mode {
+            FloatingGamepadTextInputMode::SingleLine => {
+                k_EFloatingGamepadTextInputModeModeSingleLine
+            }
+            FloatingGamepadTextInputMode::MultipleLines => {
+                k_EFloatingGamepadTextInputModeModeMultipleLines
+            }
+            FloatingGamepadTextInputMode::Email => k_EFloatingGamepadTextInputModeModeEmail,
+            FloatingGamepadTextInputMode::Numeric => k_EFloatingGamepadTextInputModeModeNumeric,
+        };
+        unsafe {
+            sys::SteamAPI_ISteamUtils_ShowFloatingGamepadTextInput(
+                self.utils, mode, x, y, width, height,
+            )
+        }
+    }
+
+    /// Closes the floating on-screen keyboard
+    pub fn dismiss_floating_gamepad_text_input(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_DismissFloatingGamepadTextInput(self.utils) }
+    }
+
+    /// Loads the image behind a steam image handle, such as the ones
+    /// returned for avatars and achievement icons.
+    ///
+    /// Returns `None` if the handle is invalid or the image isn't
+    /// loaded yet.
+    pub fn image(&self, handle: i32) -> Option<SteamImage> {
+        unsafe { load_image(self.utils, handle) }
+    }
+
+    /// Loads the text filtering dictionaries for the game's language.
+    ///
+    /// Must be called once before `filter_text`. Returns `false` if
+    /// filtering isn't available.
+    pub fn init_filter_text(&self) -> bool {
+        unsafe { sys::SteamAPI_ISteamUtils_InitFilterText(self.utils, 0) }
+    }
+
+    /// Filters profanity and slurs out of text according to the user's
+    /// settings and legal requirements of their region.
+    ///
+    /// `source` is the user that wrote the text, text from the local
+    /// user or the game itself isn't filtered for `Chat`. Text after a
+    /// nul byte is dropped.
+    pub fn filter_text(
+        &self,
+        context: TextFilteringContext,
+        source: SteamId,
+        text: &str,
+    ) -> String {
+        let context = match context {
+            TextFilteringContext::Unknown => {
+                sys::ETextFilteringContext::k_ETextFilteringContextUnknown
+            }
+            TextFilteringContext::GameContent => {
+                sys::ETextFilteringContext::k_ETextFilteringContextGameContent
+            }
+            TextFilteringContext::Chat => sys::ETextFilteringContext::k_ETextFilteringContextChat,
+            TextFilteringContext::Name => sys::ETextFilteringContext::k_ETextFilteringContextName,
+        };
+        let (input, mut buffer) = filter_text_buffers(text);
+        unsafe {
+            sys::SteamAPI_ISteamUtils_FilterText(
+                self.utils,
+                context,
+                source.0,
+                input.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len() as u32,
+            );
+        }
+        string_from_buffer(&buffer)
+    }
+
     /// Sets the position on the screen where popups from the steam overlay
     /// should appear and display themselves in.
     pub fn set_overlay_notification_position(&self, position: NotificationPosition) {
@@ -106,13 +391,19 @@ impl<Manager> Utils<Manager> {
     /// the message itself.
     ///
     /// See [Steamwork's debugging page](https://partner.steamgames.com/doc/sdk/api/debugging) for more info.
+    ///
+    /// A panic in the callback is returned from the next
+    /// `SingleClient::try_run_callbacks` (or resumed by `run_callbacks`).
+    ///
+    /// With the `tracing` feature the warnings are also emitted as events
+    /// with the `steamworks::sdk` target, whether a callback is set or not.
     pub fn set_warning_callback<F>(&self, cb: F)
     where
         F: Fn(i32, &CStr) + Send + Sync + 'static,
     {
         let mut lock = WARNING_CALLBACK
             .write()
-            .expect("warning func lock poisoned");
+            .unwrap_or_else(|err| err.into_inner());
         *lock = Some(Box::new(cb));
         unsafe {
             sys::SteamAPI_ISteamUtils_SetWarningMessageHook(self.utils, Some(c_warning_callback));
@@ -120,6 +411,117 @@ impl<Manager> Utils<Manager> {
     }
 }
 
+/// Called when steam wants to shut down
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SteamShutdown;
+
+unsafe impl Callback for SteamShutdown {
+    const ID: i32 = CALLBACK_BASE_ID + 4;
+    const SIZE: i32 = ::std::mem::size_of::<sys::SteamShutdown_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        SteamShutdown
+    }
+}
+
+/// Called when running on a laptop with less than 10 minutes of
+/// battery left, and then every minute after that
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LowBatteryPower {
+    /// The estimated number of minutes of battery left
+    pub minutes_left: u8,
+}
+
+unsafe impl Callback for LowBatteryPower {
+    const ID: i32 = CALLBACK_BASE_ID + 2;
+    const SIZE: i32 = ::std::mem::size_of::<sys::LowBatteryPower_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::LowBatteryPower_t);
+        LowBatteryPower {
+            minutes_left: val.m_nMinutesBatteryLeft,
+        }
+    }
+}
+
+pub(crate) unsafe fn load_image(utils: *mut sys::ISteamUtils, handle: c_int) -> Option<SteamImage> {
+    // 0 means no image is set and -1 that it is still loading
+    if handle <= 0 {
+        return None;
+    }
+    let mut width = 0;
+    let mut height = 0;
+    if !sys::SteamAPI_ISteamUtils_GetImageSize(utils, handle, &mut width, &mut height) {
+        return None;
+    }
+    let mut rgba = vec![0; width as usize * height as usize * 4];
+    if !sys::SteamAPI_ISteamUtils_GetImageRGBA(
+        utils,
+        handle,
+        rgba.as_mut_ptr(),
+        rgba.len() as c_int,
+    ) {
+        return None;
+    }
+    Some(SteamImage {
+        width,
+        height,
+        rgba,
+    })
+}
+
+unsafe fn entered_gamepad_text_input(utils: *mut sys::ISteamUtils) -> Option<String> {
+    // The length includes the nul terminator
+    let len = sys::SteamAPI_ISteamUtils_GetEnteredGamepadTextLength(utils).max(1);
+    let mut buffer = vec![0 as c_char; len as usize];
+    if sys::SteamAPI_ISteamUtils_GetEnteredGamepadTextInput(utils, buffer.as_mut_ptr(), len) {
+        Some(string_from_buffer(&buffer))
+    } else {
+        None
+    }
+}
+
+/// Called when the gamepad text input dialog is closed
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GamepadTextInputDismissed {
+    /// The entered text, or `None` if the user cancelled the dialog
+    pub text: Option<String>,
+}
+
+unsafe impl Callback for GamepadTextInputDismissed {
+    const ID: i32 = CALLBACK_BASE_ID + 14;
+    const SIZE: i32 = ::std::mem::size_of::<sys::GamepadTextInputDismissed_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GamepadTextInputDismissed_t);
+        // The dialog only exists on clients, so the text can be read
+        // from the client's utils interface here
+        let text = if val.m_bSubmitted {
+            entered_gamepad_text_input(sys::SteamAPI_SteamUtils_v010())
+        } else {
+            None
+        };
+        GamepadTextInputDismissed { text }
+    }
+}
+
+/// Called when the floating on-screen keyboard is closed
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FloatingGamepadTextInputDismissed;
+
+unsafe impl Callback for FloatingGamepadTextInputDismissed {
+    const ID: i32 = CALLBACK_BASE_ID + 38;
+    const SIZE: i32 = ::std::mem::size_of::<sys::FloatingGamepadTextInputDismissed_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        FloatingGamepadTextInputDismissed
+    }
+}
+
 pub(crate) struct SteamParamStringArray(Vec<*mut i8>);
 impl Drop for SteamParamStringArray {
     fn drop(&mut self) {
@@ -148,3 +550,63 @@ impl SteamParamStringArray {
         }
     }
 }
+
+/// Prepares the input and output buffers for `FilterText`
+fn filter_text_buffers(text: &str) -> (CString, Vec<c_char>) {
+    // Text from other players may contain nul bytes, steam only
+    // sees the text up to the first one anyway
+    let text = text.split('\0').next().unwrap_or_default();
+    let input = CString::new(text).unwrap();
+    // Filtered characters are replaced one for one, so the output
+    // never needs more room than the input
+    let buffer = vec![0 as c_char; input.as_bytes_with_nul().len()];
+    (input, buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steam_shutdown_callback() {
+        // The fake backend never shuts down, so the id isn't covered
+        // by its tests
+        assert_eq!(SteamShutdown::ID, sys::SteamShutdown_t_k_iCallback as i32);
+    }
+
+    #[test]
+    fn filter_text_buffers_stop_at_nul() {
+        let (input, buffer) = filter_text_buffers("what the heck");
+        assert_eq!(input.as_bytes(), b"what the heck");
+        assert_eq!(buffer.len(), "what the heck".len() + 1);
+
+        let (input, buffer) = filter_text_buffers("hidden\0text");
+        assert_eq!(input.as_bytes(), b"hidden");
+        assert_eq!(buffer.len(), 7);
+
+        let (input, buffer) = filter_text_buffers("");
+        assert_eq!(input.as_bytes(), b"");
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn steam_image_to_rgba_image() {
+        let img = SteamImage {
+            width: 2,
+            height: 1,
+            rgba: vec![255, 0, 0, 255, 0, 0, 255, 255],
+        };
+        let img = image::RgbaImage::try_from(img).unwrap();
+        assert_eq!(img.dimensions(), (2, 1));
+        assert_eq!(img.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(1, 0).0, [0, 0, 255, 255]);
+
+        let img = SteamImage {
+            width: 2,
+            height: 2,
+            rgba: vec![255; 8],
+        };
+        assert_eq!(image::RgbaImage::try_from(img.clone()), Err(img));
+    }
+}
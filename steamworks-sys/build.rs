@@ -38,25 +38,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         panic!("Unsupported OS");
     };
 
-    if triple.contains("windows") {
-        let dll_file = format!("{}.dll", lib);
-        let lib_file = format!("{}.lib", lib);
-        fs::copy(link_path.join(&dll_file), out_path.join(dll_file))?;
-        fs::copy(link_path.join(&lib_file), out_path.join(lib_file))?;
-    } else if triple.contains("darwin") {
-        fs::copy(
-            link_path.join("libsteam_api.dylib"),
-            out_path.join("libsteam_api.dylib"),
-        )?;
-    } else if triple.contains("linux") {
-        fs::copy(
-            link_path.join("libsteam_api.so"),
-            out_path.join("libsteam_api.so"),
-        )?;
-    }
+    // With the `dynamic` feature the library is opened at runtime instead
+    let dynamic = env::var_os("CARGO_FEATURE_DYNAMIC").is_some();
+    if !dynamic {
+        if triple.contains("windows") {
+            let dll_file = format!("{}.dll", lib);
+            let lib_file = format!("{}.lib", lib);
+            fs::copy(link_path.join(&dll_file), out_path.join(dll_file))?;
+            fs::copy(link_path.join(&lib_file), out_path.join(lib_file))?;
+        } else if triple.contains("darwin") {
+            fs::copy(
+                link_path.join("libsteam_api.dylib"),
+                out_path.join("libsteam_api.dylib"),
+            )?;
+        } else if triple.contains("linux") {
+            fs::copy(
+                link_path.join("libsteam_api.so"),
+                out_path.join("libsteam_api.so"),
+            )?;
+        }
 
-    println!("cargo:rustc-link-search={}", out_path.display());
-    println!("cargo:rustc-link-lib=dylib={}", lib);
+        println!("cargo:rustc-link-search={}", out_path.display());
+        println!("cargo:rustc-link-lib=dylib={}", lib);
+    }
 
     #[cfg(feature = "rebuild-bindings")]
     {
@@ -96,5 +100,158 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .expect("Couldn't write bindings!");
     }
 
+    if dynamic {
+        let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+        let binding_path = Path::new(&format!("src/{}_bindings.rs", target_os)).to_owned();
+        println!("cargo:rerun-if-changed={}", binding_path.display());
+        let bindings = fs::read_to_string(&binding_path)?;
+        fs::write(
+            out_path.join("dynamic_bindings.rs"),
+            dynamic_bindings(&bindings, &target_os),
+        )?;
+    }
+
     Ok(())
 }
+
+/// Rewrites every steam function in the `extern "C"` blocks of the bindings into
+/// a function that calls through a lazily resolved `crate::dynamic::Symbol`.
+///
+/// Statics and other functions are kept as they are.
+fn dynamic_bindings(bindings: &str, target_os: &str) -> String {
+    let mut out = String::with_capacity(bindings.len() * 2);
+    let mut rest = bindings;
+    while let Some(start) = rest.find("extern \"C\" {\n") {
+        out.push_str(&rest[..start]);
+        let block = &rest[start..];
+        let end = block.find("\n}\n").expect("unterminated extern block") + 3;
+        let (block, tail) = block.split_at(end);
+        rest = tail;
+        // Statics and the C library functions pulled in by the headers stay linked
+        if !is_steam_function(block) {
+            out.push_str(block);
+            continue;
+        }
+
+        let body = &block["extern \"C\" {\n".len()..block.len() - 3];
+        let mut attrs = Vec::new();
+        let mut link_name = None;
+        let mut item = String::new();
+        for line in body.lines() {
+            let line = line.trim();
+            if let Some(name) = line.strip_prefix("#[link_name = \"") {
+                let name = name.trim_end_matches("\"]").trim_start_matches("\\u{1}");
+                // Mach-O symbols carry an extra leading underscore that dlsym adds itself
+                let name = if target_os == "macos" {
+                    name.strip_prefix('_').unwrap_or(name)
+                } else {
+                    name
+                };
+                link_name = Some(name.to_owned());
+            } else if line.starts_with("#[") && item.is_empty() {
+                attrs.push(line);
+            } else {
+                item.push_str(line);
+                item.push(' ');
+            }
+        }
+
+        let item = item
+            .trim()
+            .trim_start_matches("pub fn ")
+            .trim_end_matches(';');
+        let open = item.find('(').unwrap();
+        let name = &item[..open];
+        let close = matching_paren(item, open);
+        let params = split_params(&item[open + 1..close]);
+        let ret = item[close + 1..].trim();
+        let symbol = link_name.unwrap_or_else(|| name.to_owned());
+
+        let names: Vec<_> = params.iter().map(|(n, _)| n.as_str()).collect();
+        let types: Vec<_> = params.iter().map(|(_, t)| t.as_str()).collect();
+        let params: Vec<_> = params
+            .iter()
+            .map(|(n, t)| format!("{}: {}", n, t))
+            .collect();
+        for attr in attrs {
+            out.push_str(attr);
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "pub unsafe fn {name}({params}) {ret} {{\n    \
+             static SYMBOL: crate::dynamic::Symbol = crate::dynamic::Symbol::new(\"{symbol}\\0\");\n    \
+             let f = ::std::mem::transmute::<*const ::std::os::raw::c_void, unsafe extern \"C\" fn({types}) {ret}>(SYMBOL.get());\n    \
+             f({names})\n}}\n",
+            name = name,
+            params = params.join(", "),
+            ret = ret,
+            symbol = symbol,
+            types = types.join(", "),
+            names = names.join(", "),
+        ));
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_steam_function(block: &str) -> bool {
+    let name = match block.split("pub fn ").nth(1) {
+        Some(item) => &item[..item.find('(').unwrap()],
+        None => return false,
+    };
+    name.contains("Steam")
+        || name.starts_with("CGameID_")
+        || name.starts_with("servernetadr_t_")
+        || name.starts_with("gameserveritem_t_")
+}
+
+fn matching_paren(s: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in s.char_indices().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    panic!("unbalanced parentheses in {}", s);
+}
+
+/// Splits `name: type` pairs on the commas that aren't nested in a type
+fn split_params(params: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    let mut prev = ' ';
+    for c in params.chars() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' => depth -= 1,
+            // `->` in function pointer types isn't a closing bracket
+            '>' if prev != '-' => depth -= 1,
+            ',' if depth == 0 => {
+                out.push(std::mem::take(&mut current));
+                prev = c;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        prev = c;
+    }
+    out.push(current);
+    out.into_iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| {
+            let (name, ty) = p
+                .split_once(':')
+                .unwrap_or_else(|| panic!("bad parameter {:?} in {:?}", p, params));
+            (name.trim().to_owned(), ty.trim().to_owned())
+        })
+        .collect()
+}
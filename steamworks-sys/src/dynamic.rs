@@ -0,0 +1,159 @@
+//! Runtime loading of the steam api library.
+//!
+//! With the `dynamic` feature the crate doesn't link against `steam_api`.
+//! Instead the library is opened with [`load_library`] and every function
+//! looks up its symbol the first time it is called.
+
+use std::error::Error;
+use std::ffi::{c_void, CString, OsStr};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::OnceLock;
+
+/// The file name of the steam api library for the target platform
+#[cfg(all(target_os = "windows", target_pointer_width = "64"))]
+pub const LIBRARY_NAME: &str = "steam_api64.dll";
+/// The file name of the steam api library for the target platform
+#[cfg(all(target_os = "windows", target_pointer_width = "32"))]
+pub const LIBRARY_NAME: &str = "steam_api.dll";
+/// The file name of the steam api library for the target platform
+#[cfg(target_os = "macos")]
+pub const LIBRARY_NAME: &str = "libsteam_api.dylib";
+/// The file name of the steam api library for the target platform
+#[cfg(target_os = "linux")]
+pub const LIBRARY_NAME: &str = "libsteam_api.so";
+
+struct Library {
+    library: libloading::Library,
+    path: PathBuf,
+}
+
+static LIBRARY: OnceLock<Library> = OnceLock::new();
+
+/// Returned when the steam api library couldn't be loaded
+#[derive(Debug)]
+pub struct LoadError {
+    path: PathBuf,
+    reason: String,
+}
+
+impl LoadError {
+    /// The path that was being loaded
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to load the steam api library from {}: {}",
+            self.path.display(),
+            self.reason
+        )
+    }
+}
+
+impl Error for LoadError {}
+
+/// Loads the steam api library from the given path.
+///
+/// The path is passed to the platform's loader as is, so a bare file name
+/// is looked up in the usual library search paths. Only one library can be
+/// loaded per process, loading the same path again does nothing.
+pub fn load_library<P: AsRef<OsStr>>(path: P) -> Result<(), LoadError> {
+    let path = PathBuf::from(path.as_ref());
+    if let Some(loaded) = LIBRARY.get() {
+        return if loaded.path == path {
+            Ok(())
+        } else {
+            Err(LoadError {
+                path,
+                reason: format!("{} is already loaded", loaded.path.display()),
+            })
+        };
+    }
+    let library = unsafe { libloading::Library::new(&path) }.map_err(|err| LoadError {
+        path: path.clone(),
+        reason: err.to_string(),
+    })?;
+    // Losing a race to another thread just drops our handle again
+    let loaded = LIBRARY.get_or_init(|| Library {
+        library,
+        path: path.clone(),
+    });
+    if loaded.path == path {
+        Ok(())
+    } else {
+        Err(LoadError {
+            path,
+            reason: format!("{} is already loaded", loaded.path.display()),
+        })
+    }
+}
+
+/// Returns whether a steam api library has been loaded
+pub fn is_library_loaded() -> bool {
+    LIBRARY.get().is_some()
+}
+
+/// Returns whether the loaded library exports the given function
+pub fn has_symbol(name: &str) -> bool {
+    let name = match CString::new(name) {
+        Ok(name) => name,
+        Err(_) => return false,
+    };
+    lookup(name.as_bytes_with_nul()).is_ok()
+}
+
+fn lookup(name: &[u8]) -> Result<*const c_void, String> {
+    let library = LIBRARY
+        .get()
+        .ok_or_else(|| "the steam api library hasn't been loaded".to_owned())?;
+    unsafe {
+        library
+            .library
+            .get::<*const c_void>(name)
+            .map(|symbol| *symbol)
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// A function in the steam api library, resolved on first use
+#[doc(hidden)]
+pub struct Symbol {
+    name: &'static str,
+    address: AtomicPtr<c_void>,
+}
+
+impl Symbol {
+    pub const fn new(name: &'static str) -> Symbol {
+        Symbol {
+            name,
+            address: AtomicPtr::new(std::ptr::null_mut()),
+        }
+    }
+
+    /// Returns the address of the function.
+    ///
+    /// Panics if the library isn't loaded or doesn't export the function.
+    pub fn get(&self) -> *const c_void {
+        let address = self.address.load(Ordering::Acquire);
+        if !address.is_null() {
+            return address;
+        }
+        match lookup(self.name.as_bytes()) {
+            Ok(address) => {
+                self.address.store(address as *mut _, Ordering::Release);
+                address
+            }
+            Err(err) => panic!(
+                "failed to resolve {}: {}",
+                self.name.trim_end_matches('\0'),
+                err
+            ),
+        }
+    }
+}
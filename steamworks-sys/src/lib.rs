@@ -1,12 +1,21 @@
 #![allow(non_camel_case_types)]
 #![allow(non_upper_case_globals)]
 #![allow(non_snake_case)]
+#![cfg_attr(feature = "dynamic", allow(clippy::missing_safety_doc))]
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "dynamic")))]
 include!("windows_bindings.rs");
 
-#[cfg(target_os = "macos")]
+#[cfg(all(target_os = "macos", not(feature = "dynamic")))]
 include!("macos_bindings.rs");
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "dynamic")))]
 include!("linux_bindings.rs");
+
+#[cfg(feature = "dynamic")]
+include!(concat!(env!("OUT_DIR"), "/dynamic_bindings.rs"));
+
+#[cfg(feature = "dynamic")]
+mod dynamic;
+#[cfg(feature = "dynamic")]
+pub use crate::dynamic::*;
@@ -0,0 +1,24 @@
+#![cfg(all(feature = "dynamic", target_os = "linux"))]
+
+use std::path::Path;
+use steamworks_sys::*;
+
+#[test]
+fn load_errors() {
+    let err = load_library("/nonexistent/libsteam_api.so").unwrap_err();
+    assert_eq!(err.path(), Path::new("/nonexistent/libsteam_api.so"));
+    assert!(err.to_string().contains("/nonexistent/libsteam_api.so"));
+    assert!(!is_library_loaded());
+
+    // Any shared object works as a stand in, missing functions only
+    // fail once they are called
+    load_library("libc.so.6").unwrap();
+    load_library("libc.so.6").unwrap();
+    assert!(is_library_loaded());
+    assert!(has_symbol("malloc"));
+    assert!(!has_symbol("SteamAPI_Init"));
+    let result = std::panic::catch_unwind(|| unsafe { SteamAPI_Init() });
+    assert!(result.is_err());
+
+    assert!(load_library("libm.so.6").is_err());
+}
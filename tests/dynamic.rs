@@ -0,0 +1,15 @@
+//! Runs in its own process because the loaded library is global and
+//! every later steam call would resolve against the stand in.
+#![cfg(all(feature = "dynamic", not(feature = "fake"), target_os = "linux"))]
+
+use steamworks::*;
+
+#[test]
+fn init_without_steam_api() {
+    // libc stands in for a library that doesn't export the steam api
+    load_steam_api("libc.so.6").unwrap();
+    let err = Client::init().err().unwrap();
+    assert_eq!(err.reason, InitErrorReason::LibraryNotLoaded);
+    assert!(err.message.contains("SteamAPI_Init"));
+    assert!(load_steam_api("/nonexistent/libsteam_api.so").is_err());
+}